@@ -1,8 +1,12 @@
 use clap::Parser;
-use liquid_doc_parser::parse_liquid_string;
-use serde_json;
+use liquid_doc_parser::{
+    check_content_for_calls, check_render_calls, check_theme_param_usage, doc_coverage,
+    find_unused_files, infer_doc_stub, liquid_ast_schema, migrate_legacy_doc, parse_liquid_file,
+    parse_liquid_string, to_binary, write_liquid_json, LiquidFile, ParseOptions, RenderGraph,
+    Severity, StreamFormat, ThemeIndex,
+};
 use std::fs;
-use std::io::{self, Read};
+use std::io::{self, Read, Write};
 
 #[derive(Parser)]
 #[command(name = "liquid-doc-parser")]
@@ -12,14 +16,78 @@ struct Cli {
     #[arg(long, short)]
     input: Option<String>,
 
-    /// Output format
+    /// Output format: json, ndjson, binary or debug, for --graph json, dot or
+    /// mermaid, or for --coverage json, markdown or table
     #[arg(long, short, default_value = "json")]
     format: String,
+
+    /// Read the input as a whole template and parse each {% doc %} block in it
+    #[arg(long)]
+    file: bool,
+
+    /// Print a {% doc %} block inferred from how the input template reads its variables
+    #[arg(long)]
+    stub: bool,
+
+    /// Print the input template with its leading "Accepts:" comment replaced by a {% doc %} block
+    #[arg(long)]
+    migrate: bool,
+
+    /// Check the {% render %} and {% content_for 'block' %} calls of the theme in this directory
+    /// against the docs of the snippets and blocks they render, and each doc against its body
+    #[arg(long, value_name = "THEME_DIR")]
+    check_theme: Option<String>,
+
+    /// Print which files of the theme in this directory render which, as json, dot or mermaid
+    #[arg(long, value_name = "THEME_DIR")]
+    graph: Option<String>,
+
+    /// List the snippets and blocks of the theme in this directory that nothing renders
+    #[arg(long, value_name = "THEME_DIR")]
+    unused: Option<String>,
+
+    /// Report how much of the snippets and blocks of the theme in this directory is documented,
+    /// as json, markdown or table
+    #[arg(long, value_name = "THEME_DIR")]
+    coverage: Option<String>,
+
+    /// With --coverage, fail when the coverage score is below this percentage
+    #[arg(long, value_name = "PERCENT", requires = "coverage")]
+    min_coverage: Option<f64>,
+
+    /// Print the JSON Schema of the JSON output and exit
+    #[arg(long)]
+    schema: bool,
 }
 
 fn main() -> io::Result<()> {
     let cli = Cli::parse();
 
+    if cli.schema {
+        println!("{:#}", liquid_ast_schema());
+        return Ok(());
+    }
+
+    if let Some(root) = cli.check_theme {
+        return check_theme(&root);
+    }
+
+    if let Some(root) = cli.graph {
+        return write_render_graph(&root, &cli.format);
+    }
+
+    if let Some(root) = cli.coverage {
+        return write_coverage(&root, &cli.format, cli.min_coverage);
+    }
+
+    if let Some(root) = cli.unused {
+        let unused = find_unused_files(&ThemeIndex::load(root)?);
+        let mut stdout = io::BufWriter::new(io::stdout().lock());
+        serde_json::to_writer_pretty(&mut stdout, &unused)?;
+        writeln!(stdout)?;
+        return stdout.flush();
+    }
+
     // Read input from file or stdin
     let input_content = match cli.input {
         Some(file_path) => fs::read_to_string(file_path)?,
@@ -30,29 +98,91 @@ fn main() -> io::Result<()> {
         }
     };
 
-    // Parse the liquid content
-    match parse_liquid_string(&input_content, None) {
-        Some(result) => {
-            match cli.format.as_str() {
-                "json" => {
-                    // Convert to JSON for display
-                    match serde_json::to_string_pretty(&result) {
-                        Ok(json) => println!("{}", json),
-                        Err(e) => {
-                            eprintln!("Error serializing result to JSON: {}", e);
-                            std::process::exit(1);
-                        }
+    if cli.stub {
+        match infer_doc_stub(&input_content) {
+            Some(stub) => match stub.to_text() {
+                Ok(text) => {
+                    for name in &stub.skipped {
+                        eprintln!("Skipped '{}', which cannot be a @param name", name);
                     }
+                    print!("{}", text);
                 }
-                "debug" => {
-                    println!("{:#?}", result);
-                }
-                _ => {
-                    eprintln!("Unsupported format: {}", cli.format);
+                Err(e) => {
+                    eprintln!("Error building the doc stub: {}", e);
                     std::process::exit(1);
                 }
+            },
+            None => {
+                eprintln!("The template already has a {{% doc %}} block");
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    if cli.migrate {
+        let migration = match migrate_legacy_doc(&input_content) {
+            Some(Ok(migration)) => migration,
+            Some(Err(diagnostic)) => {
+                eprintln!("{}: {}", diagnostic.code, diagnostic.message);
+                std::process::exit(1);
+            }
+            None => {
+                eprintln!("The template has no comment-style doc to migrate");
+                std::process::exit(1);
             }
+        };
+        for diagnostic in &migration.diagnostics {
+            eprintln!("{}: {}", diagnostic.code, diagnostic.message);
         }
+        let position = migration.edit.position;
+        let mut migrated = input_content;
+        migrated.replace_range(position.start..position.end, &migration.edit.new_text);
+        print!("{}", migrated);
+        return Ok(());
+    }
+
+    if cli.file {
+        return write_liquid_file(&parse_liquid_file(&input_content), &cli.format);
+    }
+
+    // JSON is written node by node rather than from a whole AST
+    let stream_format = match cli.format.as_str() {
+        "json" => Some(StreamFormat::Pretty),
+        "ndjson" => Some(StreamFormat::Ndjson),
+        _ => None,
+    };
+    if let Some(format) = stream_format {
+        let mut stdout = io::BufWriter::new(io::stdout().lock());
+        if let Err(e) = write_liquid_json(&input_content, &ParseOptions::new(), &mut stdout, format)
+        {
+            eprintln!("Error writing liquid content as JSON: {}", e);
+            std::process::exit(1);
+        }
+        if format == StreamFormat::Pretty {
+            writeln!(stdout)?;
+        }
+        return Ok(());
+    }
+
+    // Parse the liquid content
+    match parse_liquid_string(&input_content, None) {
+        Some(result) => match cli.format.as_str() {
+            "binary" => match to_binary(&result) {
+                Ok(bytes) => io::stdout().write_all(&bytes)?,
+                Err(e) => {
+                    eprintln!("Error encoding result: {}", e);
+                    std::process::exit(1);
+                }
+            },
+            "debug" => {
+                println!("{:#?}", result);
+            }
+            _ => {
+                eprintln!("Unsupported format: {}", cli.format);
+                std::process::exit(1);
+            }
+        },
         None => {
             eprintln!("Error parsing liquid content");
             std::process::exit(1);
@@ -61,3 +191,108 @@ fn main() -> io::Result<()> {
 
     Ok(())
 }
+
+fn write_liquid_file(file: &LiquidFile, format: &str) -> io::Result<()> {
+    let mut stdout = io::BufWriter::new(io::stdout().lock());
+    match format {
+        "json" => {
+            serde_json::to_writer_pretty(&mut stdout, file)?;
+            writeln!(stdout)?;
+        }
+        "ndjson" => {
+            for block in &file.doc_blocks {
+                serde_json::to_writer(&mut stdout, block)?;
+                writeln!(stdout)?;
+            }
+        }
+        "debug" => writeln!(stdout, "{:#?}", file)?,
+        _ => {
+            eprintln!("Unsupported format for a whole file: {}", format);
+            std::process::exit(1);
+        }
+    }
+    stdout.flush()
+}
+
+/// Prints the theme's diagnostics as JSON and fails when any of them is an error.
+fn check_theme(root: &str) -> io::Result<()> {
+    let index = ThemeIndex::load(root)?;
+    let mut diagnostics = check_render_calls(&index);
+    diagnostics.extend(check_content_for_calls(&index));
+    diagnostics.extend(check_theme_param_usage(&index));
+    diagnostics.sort_by_key(|d| (d.path.clone(), d.diagnostic.position.start));
+
+    let mut stdout = io::BufWriter::new(io::stdout().lock());
+    serde_json::to_writer_pretty(&mut stdout, &diagnostics)?;
+    writeln!(stdout)?;
+    stdout.flush()?;
+
+    if diagnostics
+        .iter()
+        .any(|d| d.diagnostic.severity == Severity::Error)
+    {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Prints the render graph of the theme, and its cycles and dynamic renders on stderr.
+fn write_render_graph(root: &str, format: &str) -> io::Result<()> {
+    let graph = RenderGraph::build(&ThemeIndex::load(root)?);
+    let mut stdout = io::BufWriter::new(io::stdout().lock());
+    match format {
+        "json" => {
+            serde_json::to_writer_pretty(&mut stdout, &graph)?;
+            writeln!(stdout)?;
+        }
+        "dot" => write!(stdout, "{}", graph.to_dot())?,
+        "mermaid" => write!(stdout, "{}", graph.to_mermaid())?,
+        _ => {
+            eprintln!("Unsupported format for a render graph: {}", format);
+            std::process::exit(1);
+        }
+    }
+    stdout.flush()?;
+
+    for cycle in &graph.cycles {
+        let paths: Vec<_> = cycle.iter().map(|path| path.to_string_lossy()).collect();
+        eprintln!("Cycle: {}", paths.join(" -> "));
+    }
+    for edge in graph.dynamic_edges() {
+        let at = edge.position.map_or(String::new(), |position| {
+            format!(" at byte {}", position.start)
+        });
+        eprintln!("Dynamic target: {}{}", edge.from.to_string_lossy(), at);
+    }
+    Ok(())
+}
+
+/// Prints the theme's doc coverage, failing when its score is below `min_coverage`.
+fn write_coverage(root: &str, format: &str, min_coverage: Option<f64>) -> io::Result<()> {
+    let report = doc_coverage(&ThemeIndex::load(root)?);
+    let mut stdout = io::BufWriter::new(io::stdout().lock());
+    match format {
+        "json" => {
+            serde_json::to_writer_pretty(&mut stdout, &report)?;
+            writeln!(stdout)?;
+        }
+        "markdown" => write!(stdout, "{}", report.to_markdown())?,
+        "table" => write!(stdout, "{}", report.to_table())?,
+        _ => {
+            eprintln!("Unsupported format for a coverage report: {}", format);
+            std::process::exit(1);
+        }
+    }
+    stdout.flush()?;
+
+    if let Some(threshold) = min_coverage {
+        if !report.meets(threshold) {
+            eprintln!(
+                "Documentation coverage {:.2}% is below the minimum of {}%",
+                report.score, threshold
+            );
+            std::process::exit(1);
+        }
+    }
+    Ok(())
+}
@@ -1,20 +1,72 @@
-use magnus::{function, prelude::*, Error, Ruby};
-use liquid_doc_parser::parse_liquid_string;
+use std::io::{self, BufWriter, Write};
+
+use magnus::{function, prelude::*, Error, RString, Ruby, Value};
+use liquid_doc_parser::{
+    parse_liquid_file, write_liquid_json, ParseOptions, StreamError, StreamFormat,
+};
 
 fn hello(subject: String) -> String {
     format!("Hello from Rust, {subject}!")
 }
 
 fn parse_liquid(source: String) -> String {
-    match parse_liquid_string(&source, None) {
-        Some(ast) => {
-            // Convert the AST to JSON for Ruby consumption
-            match serde_json::to_string_pretty(&ast) {
-                Ok(json) => json,
-                Err(e) => format!("Error serializing AST: {}", e),
+    // Write the JSON for Ruby consumption without building the AST first
+    let mut json = Vec::new();
+    match write_liquid_json(
+        &source,
+        &ParseOptions::new(),
+        &mut json,
+        StreamFormat::Pretty,
+    ) {
+        Ok(()) => String::from_utf8(json).expect("serde_json writes UTF-8"),
+        Err(StreamError::Unparseable) => "Failed to parse liquid template".to_string(),
+        Err(e) => format!("Error serializing AST: {}", e),
+    }
+}
+
+/// Passes written bytes to a Ruby IO's `write`, keeping the first Ruby error to re-raise.
+struct RubyIo {
+    io: Value,
+    error: Option<Error>,
+}
+
+impl Write for RubyIo {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.io.funcall::<_, _, Value>("write", (RString::from_slice(buf),)) {
+            Ok(_) => Ok(buf.len()),
+            Err(error) => {
+                let message = error.to_string();
+                self.error.get_or_insert(error);
+                Err(io::Error::other(message))
             }
         }
-        None => "Failed to parse liquid template".to_string(),
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn parse_to(ruby: &Ruby, source: String, io: Value) -> Result<(), Error> {
+    // Write the JSON to the IO in chunks, so the whole of it is never held as one string
+    let mut output = RubyIo { io, error: None };
+    let result = write_liquid_json(
+        &source,
+        &ParseOptions::new(),
+        BufWriter::new(&mut output),
+        StreamFormat::Pretty,
+    );
+    if let Some(error) = output.error {
+        return Err(error);
+    }
+    result.map_err(|e| Error::new(ruby.exception_runtime_error(), e.to_string()))
+}
+
+fn parse_file(source: String) -> String {
+    // Whole templates, e.g. snippet files, with one AST per {% doc %} block
+    match serde_json::to_string_pretty(&parse_liquid_file(&source)) {
+        Ok(json) => json,
+        Err(e) => format!("Error serializing doc blocks: {}", e),
     }
 }
 
@@ -22,6 +74,8 @@ fn parse_liquid(source: String) -> String {
 fn init(ruby: &Ruby) -> Result<(), Error> {
     let module = ruby.define_module("LiquidDocCore")?;
     module.define_module_function("parse", function!(parse_liquid, 1))?;
+    module.define_module_function("parse_to", function!(parse_to, 2))?;
+    module.define_module_function("parse_file", function!(parse_file, 1))?;
     module.define_singleton_method("hello", function!(hello, 1))?;
     Ok(())
 }
@@ -1,3 +1,118 @@
+/**
+ * A span of the input, in bytes.
+ * Mirrors `parser/schema/liquid-doc-ast.schema.json`.
+ */
+export interface Position {
+    start: number;
+    end: number;
+}
+
+export interface TextNode {
+    type: 'TextNode';
+    value: string;
+    position: Position;
+    source: string;
+}
+
+export interface LiquidDocDescriptionNode {
+    type: 'LiquidDocDescriptionNode';
+    name: string;
+    content: LiquidNode;
+    isImplicit: boolean;
+    isInline: boolean;
+    position: Position;
+    source: string;
+}
+
+export interface LiquidDocParamNode {
+    type: 'LiquidDocParamNode';
+    name: string;
+    paramName: LiquidNode;
+    paramType?: LiquidNode | null;
+    paramDescription?: LiquidNode | null;
+    required: boolean;
+    position: Position;
+    source: string;
+}
+
+export interface LiquidDocExampleNode {
+    type: 'LiquidDocExampleNode';
+    name: string;
+    content: LiquidNode;
+    isInline: boolean;
+    /** The text on the `@example` line, when it is followed by a multiline body. */
+    title?: LiquidNode | null;
+    /** The info string of a Markdown-fenced body, e.g. `liquid`. */
+    language?: LiquidNode | null;
+    /** The code between the fences of a Markdown-fenced body. */
+    code?: LiquidNode | null;
+    position: Position;
+    source: string;
+}
+
+export interface LiquidDocUnknownTagNode {
+    type: 'LiquidDocUnknownTagNode';
+    /** The tag name without the leading `@`. */
+    name: string;
+    tagName: LiquidNode;
+    content: LiquidNode;
+    position: Position;
+    source: string;
+}
+
+export type LiquidNode =
+    | TextNode
+    | LiquidDocDescriptionNode
+    | LiquidDocParamNode
+    | LiquidDocExampleNode
+    | LiquidDocUnknownTagNode;
+
+/** The root of a parsed doc block. */
+export interface LiquidDoc {
+    type: 'LiquidDoc';
+    astVersion: number;
+    parserVersion: string;
+    position: Position;
+    positionOffset: number;
+    nodes: LiquidNode[];
+}
+
+export interface TextEdit {
+    position: Position;
+    newText: string;
+}
+
+export interface Diagnostic {
+    code: string;
+    message: string;
+    severity: 'error' | 'warning';
+    position: Position;
+    suggestion?: string;
+    fix?: TextEdit;
+}
+
+/** A `{% doc %}` block found in a template. */
+export interface DocBlock {
+    /** From the `{%` of the opening tag to the `%}` of `{% enddoc %}`. */
+    position: Position;
+    /** The content between the tags, which the AST was parsed from. */
+    bodyPosition: Position;
+    ast: LiquidDoc;
+}
+
+export interface LiquidFile {
+    docBlocks: DocBlock[];
+    diagnostics: Diagnostic[];
+}
+
+export interface ParseOptions {
+    /**
+     * Keep unknown tags as `TextNode`s, as liquid-html-parser does, instead of
+     * `LiquidDocUnknownTagNode`s. Defaults to false.
+     */
+    fallbackTextNodes?: boolean;
+}
+
 /**
  * Initializes the WebAssembly module.
  * @param wasmUrl - Optional URL to the .wasm file.
@@ -9,10 +124,21 @@ export function init(wasmUrl?: string): Promise<void>;
  * Parses a Liquid template string into an AST (Abstract Syntax Tree).
  * Automatically initializes WASM if not already done.
  * @param input - The Liquid template string.
+ * @param options - How to parse unknown tags.
  * @returns The JavaScript representation of the Liquid AST.
  * @throws Error if input is not a string or parsing fails.
  */
-export function parseLiquid(input: string): Promise<any>;
+export function parseLiquid(input: string, options?: ParseOptions): Promise<LiquidDoc>;
+
+/**
+ * Parses every {% doc %} block of a whole Liquid template, e.g. a snippet file.
+ * Automatically initializes WASM if not already done.
+ * @param input - The whole Liquid template.
+ * @param options - How to parse unknown tags.
+ * @returns The doc blocks, with their spans and ASTs, and diagnostics.
+ * @throws Error if input is not a string.
+ */
+export function parseLiquidFile(input: string, options?: ParseOptions): Promise<LiquidFile>;
 
 /**
  * Check if the WASM module is initialized
@@ -28,6 +154,7 @@ export function initWasm(wasmUrl?: string): Promise<void>;
 declare const _default: {
     init: typeof init;
     parseLiquid: typeof parseLiquid;
+    parseLiquidFile: typeof parseLiquidFile;
     isInitialized: typeof isInitialized;
 };
 
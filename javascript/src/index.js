@@ -1,5 +1,6 @@
 import initWasmModule, {
   parse_liquid as parseLiquidDocWasm,
+  parse_liquid_file as parseLiquidFileWasm,
 } from "../wasm/liquiddoc_parser.js";
 
 let wasmInitialized = false;
@@ -91,10 +92,12 @@ export async function init(wasmInput) {
  * Parses a Liquid template string into an AST (Abstract Syntax Tree).
  * Automatically initializes WASM if not already done.
  * @param {string} input The Liquid template string.
+ * @param {{ fallbackTextNodes?: boolean }} [options] With `fallbackTextNodes`, unknown tags stay
+ *   text nodes as in liquid-html-parser instead of becoming `LiquidDocUnknownTagNode`s.
  * @returns {Promise<any>} The JavaScript representation of the Liquid AST.
  * @throws {Error} If input is not a string or parsing fails.
  */
-export async function parseLiquid(input) {
+export async function parseLiquid(input, options = {}) {
   // Auto-initialize if not already done
   if (!wasmInitialized) {
     await autoInit();
@@ -106,13 +109,33 @@ export async function parseLiquid(input) {
 
   try {
     // Call the imported WASM function
-    return parseLiquidDocWasm(input);
+    return parseLiquidDocWasm(input, options.fallbackTextNodes);
   } catch (error) {
     console.error("Error parsing Liquid:", error);
     throw error;
   }
 }
 
+/**
+ * Parses every {% doc %} block of a whole Liquid template, e.g. a snippet file.
+ * Automatically initializes WASM if not already done.
+ * @param {string} input The whole Liquid template.
+ * @param {{ fallbackTextNodes?: boolean }} [options] As for `parseLiquid`.
+ * @returns {Promise<any>} The doc blocks, with their spans and ASTs, and diagnostics.
+ * @throws {Error} If input is not a string.
+ */
+export async function parseLiquidFile(input, options = {}) {
+  if (!wasmInitialized) {
+    await autoInit();
+  }
+
+  if (typeof input !== "string") {
+    throw new TypeError("Input must be a string.");
+  }
+
+  return parseLiquidFileWasm(input, options.fallbackTextNodes);
+}
+
 // Start auto-initialization immediately when module is imported
 autoInit().catch(error => {
   console.warn("Auto-initialization failed, will retry when parseLiquid is called:", error.message);
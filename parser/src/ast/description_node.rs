@@ -1,10 +1,13 @@
 use serde::{Deserialize, Serialize};
 
 use super::{position::Position, LiquidNode, TextNode};
+use crate::events::node_from_pair;
+use crate::parser::ParseOptions;
 
 const NODE_NAME: &str = "description";
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct LiquidDocDescriptionNode {
     pub name: String,
     pub position: Position,
@@ -17,7 +20,7 @@ pub struct LiquidDocDescriptionNode {
 }
 
 impl LiquidDocDescriptionNode {
-    fn new(
+    pub(crate) fn new(
         content: TextNode,
         is_implicit: bool,
         is_inline: bool,
@@ -43,17 +46,11 @@ impl LiquidDocDescriptionNode {
             pair.as_rule()
         );
 
-        let mut content = TextNode::from_pair(pair, position_offset);
-        content.trim_content_start("@description ");
-        let source_str = pair.as_str();
-
-        LiquidDocDescriptionNode::new(
-            content,
-            false,
-            true,
-            Position::from_pair(pair, position_offset),
-            source_str.to_string(),
-        )
+        let options = ParseOptions::new().with_position_offset(position_offset);
+        match node_from_pair(pair.clone(), &options) {
+            Some(LiquidNode::LiquidDocDescriptionNode(node)) => node,
+            _ => unreachable!("Expected a DescriptionNode to build a description"),
+        }
     }
     pub fn implicit(
         pair: &pest::iterators::Pair<crate::parser::Rule>,
@@ -65,18 +62,21 @@ impl LiquidDocDescriptionNode {
             pair.as_rule()
         );
 
-        let content_node = pair.clone().into_inner().next().unwrap();
-
-        let content = TextNode::from_pair(&content_node, position_offset);
-        let source_str = pair.as_str();
-
-        LiquidDocDescriptionNode::new(
-            content,
-            true,
-            true,
-            Position::from_pair(pair, position_offset),
-            source_str.to_string(),
-        )
+        let options = ParseOptions::new().with_position_offset(position_offset);
+        match node_from_pair(pair.clone(), &options) {
+            Some(LiquidNode::LiquidDocDescriptionNode(node)) => node,
+            // An empty implicit description produces no node
+            _ => {
+                let position = Position::from_pair(pair, position_offset);
+                LiquidDocDescriptionNode::new(
+                    TextNode::new(String::new(), position, String::new()),
+                    true,
+                    true,
+                    position,
+                    String::new(),
+                )
+            }
+        }
     }
     pub fn value(&self) -> &str {
         match self.content.as_ref() {
@@ -122,6 +122,50 @@ mod tests {
         assert_eq!(description_node.value(), "kdkd\n");
     }
 
+    #[test]
+    fn implicit_matches_the_parsed_node() {
+        use crate::parser::{LiquidParser, Rule};
+        use pest::Parser;
+
+        let input = "Uses \\@media\n@param {string} title";
+        let ast = parse_liquid_string(input, Some(9)).unwrap();
+        let pair = LiquidParser::parse(Rule::Document, input)
+            .unwrap()
+            .next()
+            .unwrap()
+            .into_inner()
+            .next()
+            .unwrap();
+
+        assert_eq!(
+            ast.nodes[0],
+            LiquidNode::LiquidDocDescriptionNode(super::LiquidDocDescriptionNode::implicit(
+                &pair,
+                Some(9)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_escaped_at_sign_in_implicit_description() {
+        let input = "Uses \\@media queries, mail help\\@example.com\n@param {string} title";
+        let ast = parse_liquid_string(input, None).unwrap();
+        let description_node = match ast.nodes.first() {
+            Some(LiquidNode::LiquidDocDescriptionNode(node)) => node,
+            _ => panic!("Expected a LiquidDocDescriptionNode"),
+        };
+
+        assert_eq!(ast.nodes.len(), 2);
+        assert_eq!(
+            description_node.value(),
+            "Uses @media queries, mail help@example.com\n"
+        );
+        assert_eq!(
+            description_node.source,
+            &input[..description_node.position.end]
+        );
+    }
+
     #[test]
     fn test_serialization_round_trip() {
         assert_json_output!("@description kdkd\n");
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
-use crate::parser::Rule;
+use crate::events::node_from_pair;
+use crate::parser::{ParseOptions, Rule};
 
 use super::position::Position;
 use super::text_node::TextNode;
@@ -8,6 +9,7 @@ use super::LiquidNode;
 
 const NODE_NAME: &str = "param";
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct LiquidDocParamNode {
     pub name: String,
     pub position: Position,
@@ -21,7 +23,7 @@ pub struct LiquidDocParamNode {
     pub required: bool,
 }
 impl LiquidDocParamNode {
-    fn new(
+    pub(crate) fn new(
         position: Position,
         source: String,
         param_type: Option<TextNode>,
@@ -46,39 +48,11 @@ impl LiquidDocParamNode {
             pair.as_rule()
         );
 
-        let mut inner = pair.clone().into_inner();
-
-        let first = inner.next().expect("Expected at least one inner pair");
-
-        let (param_type, name) = if let Rule::paramType = first.as_rule() {
-            (
-                Some(TextNode::without_brackets(&first, position_offset)),
-                inner.next().expect("Expected a paramName after paramType"),
-            )
-        } else {
-            (None, first)
-        };
-        let original_name_str = name.as_str();
-        let required = !original_name_str.starts_with('[') && !original_name_str.ends_with(']');
-
-        let param_name = TextNode::without_brackets(&name, position_offset);
-
-        let description = inner.next().and_then(|t| {
-            if !t.as_str().is_empty() {
-                Some(TextNode::from_pair(&t, position_offset))
-            } else {
-                None
-            }
-        });
-
-        LiquidDocParamNode::new(
-            Position::from_pair(pair, position_offset),
-            pair.as_str().to_string(),
-            param_type,
-            param_name,
-            description,
-            required,
-        )
+        let options = ParseOptions::new().with_position_offset(position_offset);
+        match node_from_pair(pair.clone(), &options) {
+            Some(LiquidNode::LiquidDocParamNode(node)) => node,
+            _ => unreachable!("Expected a ParamNode to build a param"),
+        }
     }
 }
 
@@ -168,6 +142,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_param_with_escaped_braces_in_type() {
+        let input = "@param {\\{a\\}} shape - The \\@shape of the object";
+        let node = parse_liquid_string(input, None).unwrap().head();
+        if let LiquidNode::LiquidDocParamNode(param_node) = node {
+            let param_type = param_node.param_type.unwrap();
+            let param_type = param_type.as_text_node_unsafe();
+            assert_eq!(param_type.as_str(), "{a}");
+            assert_eq!(param_type.source, "\\{a\\}");
+            assert_eq!(param_type.position.start, 8);
+            assert_eq!(param_type.position.end, 13);
+            assert_eq!(
+                param_node
+                    .param_description
+                    .unwrap()
+                    .as_text_node_unsafe()
+                    .as_str(),
+                "The @shape of the object"
+            );
+        } else {
+            panic!("Expected a LiquidDocParamNode");
+        }
+    }
+
     #[test]
     pub fn test_serialization_round_trip() {
         assert_json_output!(
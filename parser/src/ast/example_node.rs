@@ -1,9 +1,17 @@
+use std::ops::Range;
+
 use serde::{Deserialize, Serialize};
 
+use super::text_node::find_line_break;
 use super::{position::Position, LiquidNode, TextNode};
+use crate::events::node_from_pair;
+use crate::parser::ParseOptions;
 
 const NODE_NAME: &str = "example";
+const FENCE: &str = "```";
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct LiquidDocExampleNode {
     pub name: String,
     pub position: Position,
@@ -11,6 +19,15 @@ pub struct LiquidDocExampleNode {
     pub content: Box<LiquidNode>,
     #[serde(rename = "isInline")]
     pub is_inline: bool,
+    /// The text on the `@example` line, when it is followed by a multiline body.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<Box<LiquidNode>>,
+    /// The info string of a Markdown-fenced body, e.g. `liquid` in ` ```liquid `.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<Box<LiquidNode>>,
+    /// The code between the fences of a Markdown-fenced body.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Box<LiquidNode>>,
 }
 
 impl LiquidDocExampleNode {
@@ -21,6 +38,9 @@ impl LiquidDocExampleNode {
             position,
             source,
             name: NODE_NAME.to_string(),
+            title: None,
+            language: None,
+            code: None,
         }
     }
 
@@ -34,15 +54,99 @@ impl LiquidDocExampleNode {
             pair.as_rule()
         );
 
-        let mut content = TextNode::from_pair(pair, position_offset);
-        let is_inline = !pair.as_str().starts_with("@example\n");
+        let options = ParseOptions::new().with_position_offset(position_offset);
+        match node_from_pair(pair.clone(), &options) {
+            Some(LiquidNode::LiquidDocExampleNode(node)) => node,
+            _ => unreachable!("Expected an ExampleNode to build an example"),
+        }
+    }
+
+    /// Fills in `title`, `language` and `code` from the raw `content`, for nodes that were not
+    /// built by this parser.
+    pub(crate) fn with_body_parts(mut self) -> Self {
+        let content = match self.content.as_ref() {
+            LiquidNode::TextNode(text_node) => text_node.clone(),
+            _ => unreachable!("Expected content to be a TextNode"),
+        };
 
-        content.trim_content_start("@example\n");
-        content.trim_content_start("@example ");
+        let value = content.as_str();
+        let (title, body_start) = split_title(value, self.is_inline);
+        self.title = title.map(|t| Box::new(LiquidNode::TextNode(content.slice(t.start, t.end))));
+        if let Some((language, code)) = parse_fence(value, body_start) {
+            self.language =
+                language.map(|l| Box::new(LiquidNode::TextNode(content.slice(l.start, l.end))));
+            self.code = Some(Box::new(LiquidNode::TextNode(
+                content.slice(code.start, code.end),
+            )));
+        }
+        self
+    }
+}
+
+/// Splits the text on the `@example` line from the body below it.
+/// Returns the range of the title, if any, and the byte index where the body starts.
+pub(crate) fn split_title(content: &str, is_inline: bool) -> (Option<Range<usize>>, usize) {
+    if !is_inline {
+        return (None, 0);
+    }
+    let Some((line_end, line_break)) = find_line_break(content) else {
+        return (None, 0);
+    };
 
-        let position = Position::from_pair(pair, position_offset);
-        let source = pair.as_str().to_string();
-        LiquidDocExampleNode::new(content, is_inline, position, source)
+    let title = content[..line_end].trim_end();
+    let body_start = line_end + line_break;
+    if title.is_empty() || content[body_start..].trim().is_empty() {
+        return (None, 0);
+    }
+
+    (Some(0..title.len()), body_start)
+}
+
+/// Finds a Markdown-fenced block at the start of the body.
+/// Returns the range of the language in its info string, if any, and of the code between the fences.
+pub(crate) fn parse_fence(
+    content: &str,
+    body_start: usize,
+) -> Option<(Option<Range<usize>>, Range<usize>)> {
+    let body = &content[body_start..];
+    let fence_start = body_start + body.len() - body.trim_start().len();
+    if !content[fence_start..].starts_with(FENCE) {
+        return None;
+    }
+
+    let info_start = fence_start + FENCE.len();
+    let (info_len, line_break) = find_line_break(&content[info_start..])?;
+    let info = &content[info_start..info_start + info_len];
+    let language = info.split_whitespace().next().map(|word| {
+        let start = info_start + info.find(word).unwrap_or(0);
+        start..start + word.len()
+    });
+
+    let code_start = info_start + info_len + line_break;
+    let closing_fence = content[code_start..]
+        .match_indices(FENCE)
+        .map(|(index, _)| code_start + index)
+        .find(|&index| index == code_start || ends_with_line_break(&content[..index]))?;
+
+    let mut code_end = closing_fence;
+    if code_end > code_start {
+        code_end -= trailing_line_break_len(&content[code_start..code_end]);
+    }
+
+    Some((language, code_start..code_end))
+}
+
+fn ends_with_line_break(s: &str) -> bool {
+    s.ends_with('\n') || s.ends_with('\r')
+}
+
+fn trailing_line_break_len(s: &str) -> usize {
+    if s.ends_with("\r\n") {
+        2
+    } else if ends_with_line_break(s) {
+        1
+    } else {
+        0
     }
 }
 
@@ -70,6 +174,104 @@ mod test {
         }
     }
 
+    #[test]
+    fn parse_multiline_example_node_with_crlf() {
+        let input = "@example\r\nfirst line\r\nsecond line";
+        let node = parse_liquid_string(input, Some(10)).unwrap().head();
+        if let LiquidNode::LiquidDocExampleNode(example_node) = node {
+            let content = example_node.content.as_text_node_unsafe();
+            assert_eq!(content.as_str(), "first line\r\nsecond line");
+            assert_eq!(content.position.start, 20);
+            assert!(!example_node.is_inline);
+        } else {
+            panic!("Expected a LiquidDocExampleNode");
+        }
+    }
+
+    #[test]
+    fn parse_example_with_title() {
+        let input = "@example Product card\n{% render 'card', product: product %}\n";
+        let node = parse_liquid_string(input, Some(10)).unwrap().head();
+        if let LiquidNode::LiquidDocExampleNode(example_node) = node {
+            let title = example_node.title.unwrap();
+            let title = title.as_text_node_unsafe();
+            assert_eq!(title.as_str(), "Product card");
+            assert_eq!(title.position.start, 19);
+            assert_eq!(title.position.end, 31);
+            assert!(example_node.code.is_none());
+        } else {
+            panic!("Expected a LiquidDocExampleNode");
+        }
+    }
+
+    #[test]
+    fn inline_example_has_no_title() {
+        let input = "@example simple inline example\n";
+        let node = parse_liquid_string(input, None).unwrap().head();
+        if let LiquidNode::LiquidDocExampleNode(example_node) = node {
+            assert!(example_node.title.is_none());
+        } else {
+            panic!("Expected a LiquidDocExampleNode");
+        }
+    }
+
+    #[test]
+    fn parse_fenced_example() {
+        let input =
+            "@example With a fence\n```liquid\n{% render 'card' %}\n```\n@param {string} title";
+        let ast = parse_liquid_string(input, None).unwrap();
+        assert_eq!(ast.nodes.len(), 2);
+        if let LiquidNode::LiquidDocExampleNode(example_node) = ast.head() {
+            let language = example_node.language.unwrap();
+            assert_eq!(language.as_text_node_unsafe().as_str(), "liquid");
+            assert_eq!(&input[25..31], "liquid");
+            assert_eq!(language.as_text_node_unsafe().position.start, 25);
+
+            let code = example_node.code.unwrap();
+            let code = code.as_text_node_unsafe();
+            assert_eq!(code.as_str(), "{% render 'card' %}");
+            assert_eq!(
+                &input[code.position.start..code.position.end],
+                code.as_str()
+            );
+        } else {
+            panic!("Expected a LiquidDocExampleNode");
+        }
+    }
+
+    #[test]
+    fn tags_inside_fences_belong_to_the_example() {
+        let input = "@example\n```html\n<p>@param is not a tag here</p>\n```";
+        let ast = parse_liquid_string(input, None).unwrap();
+        assert_eq!(ast.nodes.len(), 1);
+        if let LiquidNode::LiquidDocExampleNode(example_node) = ast.head() {
+            assert!(example_node.title.is_none());
+            assert_eq!(
+                example_node.code.unwrap().as_text_node_unsafe().as_str(),
+                "<p>@param is not a tag here</p>"
+            );
+        } else {
+            panic!("Expected a LiquidDocExampleNode");
+        }
+    }
+
+    #[test]
+    fn unclosed_fence_is_plain_content() {
+        let input = "@example\n```json\n{}";
+        let node = parse_liquid_string(input, None).unwrap().head();
+        if let LiquidNode::LiquidDocExampleNode(example_node) = node {
+            assert!(example_node.code.is_none());
+            assert!(example_node.language.is_none());
+        } else {
+            panic!("Expected a LiquidDocExampleNode");
+        }
+    }
+
+    #[test]
+    pub fn fenced_example_with_title() {
+        assert_json_output!("@example Rendering a product card\r\n```liquid\r\n{% render 'card', product: product %}\r\n```\r\n");
+    }
+
     #[test]
     pub fn test_serialization_round_trip() {
         assert_json_output!("@example simple inline example\n");
@@ -9,10 +9,69 @@ macro_rules! generate_fixture_test {
 
                 crate::assert_json_output!(content);
             }
+
+            #[test]
+            fn [<test_fixture_crlf_ $fixture_name>]() {
+                let content = include_str!(concat!("../../../web/fixtures/", $fixture_name, ".liquid"))
+                    .replace('\n', "\r\n");
+
+                crate::assert_json_output!(&content);
+            }
+
+            #[cfg(feature = "binary")]
+            #[test]
+            fn [<test_fixture_binary_round_trip_ $fixture_name>]() {
+                let content = include_str!(concat!("../../../web/fixtures/", $fixture_name, ".liquid"));
+                let ast = crate::parser::parse_liquid_string(content, Some(10)).unwrap();
+
+                let decoded = crate::binary::from_binary(&crate::binary::to_binary(&ast).unwrap()).unwrap();
+
+                assert_eq!(decoded, ast);
+                // Compared against the snapshot of the JSON parse, not a snapshot of its own
+                let serialized = serde_json::to_string_pretty(&decoded.nodes).unwrap();
+                let name = format!("fixture_{}", $fixture_name.replace('-', "_"));
+                insta::assert_snapshot!(name.as_str(), serialized, content);
+            }
+
+            #[test]
+            fn [<test_fixture_crlf_normalized_ $fixture_name>]() {
+                let lf = include_str!(concat!("../../../web/fixtures/", $fixture_name, ".liquid"));
+                let crlf = lf.replace('\n', "\r\n");
+                let options = crate::parser::ParseOptions::new().with_normalized_line_endings(true);
+
+                let lf_ast = crate::parser::parse_liquid_string(lf, None).unwrap();
+                let crlf_ast = crate::parser::parse_liquid_string_with_options(&crlf, &options).unwrap();
+
+                // Only positions and sources differ, the normalized values match the LF parse.
+                assert_eq!(lf_ast.nodes.len(), crlf_ast.nodes.len());
+                for (lf_node, crlf_node) in lf_ast.nodes.iter().zip(crlf_ast.nodes.iter()) {
+                    assert_eq!(values(lf_node), values(crlf_node));
+                }
+            }
         }
     };
 }
 
+fn values(node: &crate::ast::LiquidNode) -> Vec<String> {
+    use crate::ast::LiquidNode;
+
+    match node {
+        LiquidNode::TextNode(node) => vec![node.value.clone()],
+        LiquidNode::LiquidDocDescriptionNode(node) => values(&node.content),
+        LiquidNode::LiquidDocExampleNode(node) => values(&node.content),
+        LiquidNode::LiquidDocUnknownTagNode(node) => values(&node.content),
+        LiquidNode::LiquidDocParamNode(node) => [
+            Some(&node.param_name),
+            node.param_type.as_ref(),
+            node.param_description.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .flat_map(|child| values(child))
+        .collect(),
+    }
+}
+
 // This macro is generated by the build script and will iterate over all files in the /web/fixtures directory
 // and generate a test for each.
 for_each_fixture_file!(generate_fixture_test);
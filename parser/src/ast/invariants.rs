@@ -0,0 +1,290 @@
+use crate::diagnostics::Diagnostic;
+
+use super::text_node::{normalize_line_endings, unescape};
+use super::{LiquidAST, LiquidNode, Position, TextNode};
+
+impl LiquidAST {
+    /// Checks the invariants of an AST parsed from `input` with `position_offset`:
+    ///
+    /// - the root spans the input and records the offset,
+    /// - every node's `source` is the input at its position,
+    /// - every `TextNode` value is its source, unescaped and/or with normalised line endings,
+    /// - child nodes nest inside their parent,
+    /// - sibling nodes are in source order and do not overlap.
+    ///
+    /// Returns every violation as an error diagnostic.
+    ///
+    /// ```
+    /// use liquid_doc_parser::parse_liquid_string;
+    ///
+    /// let input = "@param {string} title - The title";
+    /// let ast = parse_liquid_string(input, Some(9)).unwrap();
+    /// assert!(ast.validate(input, Some(9)).is_ok());
+    /// ```
+    pub fn validate(
+        &self,
+        input: &str,
+        position_offset: Option<usize>,
+    ) -> Result<(), Vec<Diagnostic>> {
+        let offset = position_offset.unwrap_or(0);
+        let mut validator = Validator {
+            input,
+            offset,
+            violations: Vec::new(),
+        };
+
+        if self.position_offset != offset
+            || self.position != Position::new(0, input.len(), position_offset)
+        {
+            validator.violation(
+                "RootMismatch",
+                format!(
+                    "The root spans {}..{} with offset {}, expected {}..{} with offset {}",
+                    self.position.start,
+                    self.position.end,
+                    self.position_offset,
+                    offset,
+                    offset + input.len(),
+                    offset
+                ),
+                self.position,
+            );
+        }
+
+        let nodes: Vec<(String, &LiquidNode)> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (format!("nodes[{}]", index), node))
+            .collect();
+        validator.siblings(&nodes, self.position);
+        for (path, node) in &nodes {
+            validator.node(path, node);
+        }
+
+        if validator.violations.is_empty() {
+            Ok(())
+        } else {
+            Err(validator.violations)
+        }
+    }
+}
+
+struct Validator<'a> {
+    input: &'a str,
+    offset: usize,
+    violations: Vec<Diagnostic>,
+}
+
+impl Validator<'_> {
+    fn violation(&mut self, code: &str, message: String, position: Position) {
+        self.violations
+            .push(Diagnostic::error(code, message, position));
+    }
+
+    fn node(&mut self, path: &str, node: &LiquidNode) {
+        let (position, source) = span(node);
+        match position
+            .start
+            .checked_sub(self.offset)
+            .zip(position.end.checked_sub(self.offset))
+            .and_then(|(start, end)| self.input.get(start..end))
+        {
+            None => self.violation(
+                "OutOfBounds",
+                format!(
+                    "{}: {}..{} is outside the input",
+                    path, position.start, position.end
+                ),
+                position,
+            ),
+            Some(slice) if slice != source => self.violation(
+                "SourceMismatch",
+                format!(
+                    "{}: source {:?} is not the input at {}..{}, {:?}",
+                    path, source, position.start, position.end, slice
+                ),
+                position,
+            ),
+            Some(_) => {}
+        }
+
+        if let LiquidNode::TextNode(text) = node {
+            self.value(path, text);
+        }
+
+        for group in child_groups(node) {
+            let group: Vec<(String, &LiquidNode)> = group
+                .into_iter()
+                .map(|(field, child)| (format!("{}.{}", path, field), child))
+                .collect();
+            self.siblings(&group, position);
+            for (child_path, child) in &group {
+                self.node(child_path, child);
+            }
+        }
+    }
+
+    fn value(&mut self, path: &str, text: &TextNode) {
+        let unescaped = unescape(&text.source);
+        let derivable = [
+            text.source.clone(),
+            normalize_line_endings(&text.source),
+            normalize_line_endings(&unescaped),
+            unescaped,
+        ];
+        if !derivable.contains(&text.value) {
+            self.violation(
+                "ValueMismatch",
+                format!(
+                    "{}: value {:?} cannot be derived from source {:?}",
+                    path, text.value, text.source
+                ),
+                text.position,
+            );
+        }
+    }
+
+    /// Checks that `nodes` nest inside `parent` and follow each other without overlapping.
+    fn siblings(&mut self, nodes: &[(String, &LiquidNode)], parent: Position) {
+        let mut previous: Option<(&str, Position)> = None;
+        for (path, node) in nodes {
+            let (position, _) = span(node);
+            if position.start < parent.start
+                || position.end > parent.end
+                || position.start > position.end
+            {
+                self.violation(
+                    "NotNested",
+                    format!(
+                        "{}: {}..{} is not inside its parent's {}..{}",
+                        path, position.start, position.end, parent.start, parent.end
+                    ),
+                    position,
+                );
+            }
+            if let Some((previous_path, previous_position)) = previous {
+                if position.start < previous_position.end {
+                    self.violation(
+                        "OutOfOrder",
+                        format!(
+                            "{}: starts at {}, before {} ends at {}",
+                            path, position.start, previous_path, previous_position.end
+                        ),
+                        position,
+                    );
+                }
+            }
+            previous = Some((path, position));
+        }
+    }
+}
+
+fn span(node: &LiquidNode) -> (Position, &str) {
+    match node {
+        LiquidNode::LiquidDocDescriptionNode(node) => (node.position, &node.source),
+        LiquidNode::TextNode(node) => (node.position, &node.source),
+        LiquidNode::LiquidDocParamNode(node) => (node.position, &node.source),
+        LiquidNode::LiquidDocExampleNode(node) => (node.position, &node.source),
+        LiquidNode::LiquidDocUnknownTagNode(node) => (node.position, &node.source),
+    }
+}
+
+/// A node's children, grouped into runs of siblings in source order.
+/// An example's title, language and code are parts of its content, so they form their own run.
+fn child_groups(node: &LiquidNode) -> Vec<Vec<(&'static str, &LiquidNode)>> {
+    match node {
+        LiquidNode::LiquidDocDescriptionNode(node) => vec![vec![("content", &node.content)]],
+        LiquidNode::TextNode(_) => vec![],
+        LiquidNode::LiquidDocParamNode(node) => vec![[
+            ("paramType", node.param_type.as_deref()),
+            ("paramName", Some(node.param_name.as_ref())),
+            ("paramDescription", node.param_description.as_deref()),
+        ]
+        .into_iter()
+        .filter_map(|(field, child)| child.map(|child| (field, child)))
+        .collect()],
+        LiquidNode::LiquidDocExampleNode(node) => vec![
+            vec![("content", node.content.as_ref())],
+            [
+                ("title", node.title.as_deref()),
+                ("language", node.language.as_deref()),
+                ("code", node.code.as_deref()),
+            ]
+            .into_iter()
+            .filter_map(|(field, child)| child.map(|child| (field, child)))
+            .collect(),
+        ],
+        LiquidNode::LiquidDocUnknownTagNode(node) => vec![vec![
+            ("tagName", node.tag_name.as_ref()),
+            ("content", node.content.as_ref()),
+        ]],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{LiquidNode, Position};
+    use crate::parser::parse_liquid_string;
+    use pretty_assertions::assert_eq;
+
+    fn codes(result: Result<(), Vec<crate::Diagnostic>>) -> Vec<String> {
+        result
+            .unwrap_err()
+            .into_iter()
+            .map(|diagnostic| diagnostic.code)
+            .collect()
+    }
+
+    #[test]
+    fn parsed_asts_are_valid() {
+        let input = "Renders a card\n@param {\\{a\\}} [shape] - The \\@shape\n@example Card\n```liquid\n{% render 'card' %}\n```\n@unsupported tag\n@prompt\nWrite a card";
+        let ast = parse_liquid_string(input, Some(9)).unwrap();
+
+        assert_eq!(ast.validate(input, Some(9)), Ok(()));
+    }
+
+    #[test]
+    fn reports_sources_that_do_not_match_the_input() {
+        let input = "@param {string} title";
+        let mut ast = parse_liquid_string(input, None).unwrap();
+        if let LiquidNode::LiquidDocParamNode(param) = &mut ast.nodes[0] {
+            if let LiquidNode::TextNode(param_type) = param.param_type.as_deref_mut().unwrap() {
+                param_type.source = "{string}".to_string();
+            }
+        }
+
+        assert_eq!(
+            codes(ast.validate(input, None)),
+            vec!["SourceMismatch", "ValueMismatch"]
+        );
+    }
+
+    #[test]
+    fn reports_children_outside_their_parent_and_unordered_siblings() {
+        let input = "@param {string} title\n@param {number} width";
+        let mut ast = parse_liquid_string(input, None).unwrap();
+        ast.nodes.swap(0, 1);
+        if let LiquidNode::LiquidDocParamNode(param) = &mut ast.nodes[1] {
+            if let LiquidNode::TextNode(name) = param.param_name.as_mut() {
+                name.position = Position::new(30, 35, None);
+            }
+        }
+
+        assert_eq!(
+            codes(ast.validate(input, None)),
+            vec!["OutOfOrder", "NotNested", "SourceMismatch"]
+        );
+    }
+
+    #[test]
+    fn reports_a_wrong_offset() {
+        let input = "Renders a card";
+        let ast = parse_liquid_string(input, Some(9)).unwrap();
+
+        assert_eq!(
+            codes(ast.validate(input, None)),
+            vec!["RootMismatch", "OutOfBounds", "OutOfBounds"]
+        );
+    }
+}
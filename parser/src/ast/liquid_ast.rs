@@ -1,13 +1,14 @@
 use crate::ast::{LiquidDocDescriptionNode, LiquidDocParamNode, TextNode};
 use serde::{Deserialize, Serialize};
 
-use super::LiquidDocExampleNode;
+use super::{position::Position, LiquidDocExampleNode, LiquidDocUnknownTagNode};
 
 /// Represents the different types of nodes in a Liquid AST.
 /// Each variant corresponds to a specific type of node in the Liquid template language.
 /// If a node has a specific content type, it is represented as a `LiquidNode` variant, rather than the more specific type.
 /// This instructs the serializer to use the `type` field to differentiate between node types, which the consuming code requires.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 #[allow(clippy::enum_variant_names)]
 pub enum LiquidNode {
@@ -15,9 +16,38 @@ pub enum LiquidNode {
     TextNode(TextNode),
     LiquidDocParamNode(LiquidDocParamNode),
     LiquidDocExampleNode(LiquidDocExampleNode),
+    LiquidDocUnknownTagNode(LiquidDocUnknownTagNode),
 }
 
 impl LiquidNode {
+    /// Rewrites line endings in the `value` of this node and all of its children.
+    /// See [`TextNode::normalize_line_endings`].
+    pub fn normalize_line_endings(&mut self) {
+        match self {
+            LiquidNode::TextNode(node) => node.normalize_line_endings(),
+            LiquidNode::LiquidDocDescriptionNode(node) => node.content.normalize_line_endings(),
+            LiquidNode::LiquidDocExampleNode(node) => {
+                node.content.normalize_line_endings();
+                if let Some(title) = node.title.as_mut() {
+                    title.normalize_line_endings();
+                }
+                if let Some(code) = node.code.as_mut() {
+                    code.normalize_line_endings();
+                }
+            }
+            LiquidNode::LiquidDocUnknownTagNode(node) => node.content.normalize_line_endings(),
+            LiquidNode::LiquidDocParamNode(node) => {
+                node.param_name.normalize_line_endings();
+                if let Some(param_type) = node.param_type.as_mut() {
+                    param_type.normalize_line_endings();
+                }
+                if let Some(param_description) = node.param_description.as_mut() {
+                    param_description.normalize_line_endings();
+                }
+            }
+        }
+    }
+
     #[cfg(test)]
     pub fn as_text_node_unsafe(&self) -> &TextNode {
         if let LiquidNode::TextNode(text_node) = self {
@@ -28,22 +58,103 @@ impl LiquidNode {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+/// Version of the `liquid_doc_parser` crate that produced an AST.
+pub const PARSER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Version of the serialised AST shape. Bump it whenever a node gains, loses or renames a field.
+pub const AST_VERSION: u32 = 2;
+
+/// The root of a parsed doc block. It serialises with `"type": "LiquidDoc"`, the span of the
+/// whole input and the versions consumers need to detect output they cannot read.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename = "LiquidDoc")]
 pub struct LiquidAST {
+    pub position: Position,
+    #[serde(rename = "positionOffset")]
+    pub position_offset: usize,
+    #[serde(rename = "parserVersion")]
+    pub parser_version: String,
+    #[serde(rename = "astVersion")]
+    pub ast_version: u32,
     pub nodes: Vec<LiquidNode>,
 }
 
+impl Default for LiquidAST {
+    fn default() -> Self {
+        LiquidAST::new()
+    }
+}
+
 impl LiquidAST {
     pub fn new() -> Self {
-        LiquidAST { nodes: Vec::new() }
+        LiquidAST {
+            position: Position::new(0, 0, None),
+            position_offset: 0,
+            parser_version: PARSER_VERSION.to_string(),
+            ast_version: AST_VERSION,
+            nodes: Vec::new(),
+        }
+    }
+
+    /// Creates an empty AST spanning `source_len` bytes, shifted by `position_offset`.
+    pub fn for_source(source_len: usize, position_offset: Option<usize>) -> Self {
+        LiquidAST {
+            position: Position::new(0, source_len, position_offset),
+            position_offset: position_offset.unwrap_or(0),
+            ..LiquidAST::new()
+        }
+    }
+
+    /// Whether this AST has the shape the current parser produces.
+    pub fn is_current_version(&self) -> bool {
+        self.ast_version == AST_VERSION
     }
 
     pub fn add_node(&mut self, node: LiquidNode) {
         self.nodes.push(node);
     }
 
+    pub fn normalize_line_endings(&mut self) {
+        for node in self.nodes.iter_mut() {
+            node.normalize_line_endings();
+        }
+    }
+
     #[allow(dead_code)]
     pub fn head(&self) -> LiquidNode {
         self.nodes[0].clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{AST_VERSION, PARSER_VERSION};
+    use crate::parser::parse_liquid_string;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn serializes_root_node() {
+        let input = "Renders a card\n@param {string} title";
+        let ast = parse_liquid_string(input, Some(9)).unwrap();
+        let json = serde_json::to_value(&ast).unwrap();
+
+        assert_eq!(json["type"], "LiquidDoc");
+        assert_eq!(json["position"]["start"], 9);
+        assert_eq!(json["position"]["end"], 9 + input.len());
+        assert_eq!(json["positionOffset"], 9);
+        assert_eq!(json["parserVersion"], PARSER_VERSION);
+        assert_eq!(json["astVersion"], AST_VERSION);
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn deserializes_root_node() {
+        let ast = parse_liquid_string("@example {% render 'card' %}", None).unwrap();
+        let json = serde_json::to_string(&ast).unwrap();
+
+        let round_tripped: super::LiquidAST = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_tripped, ast);
+        assert!(round_tripped.is_current_version());
+    }
+}
@@ -3,13 +3,17 @@ mod doc_param_node;
 mod example_node;
 #[cfg(test)]
 mod fixture_tests;
+mod invariants;
 mod liquid_ast;
 mod position;
 pub(crate) mod test_utils;
 mod text_node;
+mod unknown_tag_node;
 
 pub use description_node::*;
 pub use doc_param_node::*;
 pub use example_node::*;
 pub use liquid_ast::*;
+pub use position::Position;
 pub use text_node::*;
+pub use unknown_tag_node::*;
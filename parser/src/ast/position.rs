@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use crate::parser::Rule;
 
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Position {
     pub start: usize,
     pub end: usize,
@@ -1,10 +1,22 @@
-/// Generates a JSON snapshot of the AST for the given Liquid input.
+/// Generates a JSON snapshot of the AST for the given Liquid input, after checking the AST's invariants.
 /// If the output changes between test runs, the test will fail.
 /// To compare and accept/reject changes, use the `cargo insta review` on the command line.
 #[macro_export]
 macro_rules! assert_json_output {
     ($input:expr $(,)?) => {{
         let ast = $crate::parser::parse_liquid_string($input, Some(10)).unwrap();
+        if let Err(violations) = ast.validate($input, Some(10)) {
+            panic!("The AST breaks its invariants: {:#?}", violations);
+        }
+
+        let serialized = serde_json::to_string_pretty(&ast.nodes).unwrap();
+        insta::assert_snapshot!(insta::internals::AutoName, serialized, $input);
+    }};
+    ($input:expr, $options:expr $(,)?) => {{
+        let ast = $crate::parser::parse_liquid_string_with_options($input, &$options).unwrap();
+        if let Err(violations) = ast.validate($input, $options.position_offset) {
+            panic!("The AST breaks its invariants: {:#?}", violations);
+        }
 
         let serialized = serde_json::to_string_pretty(&ast.nodes).unwrap();
         insta::assert_snapshot!(insta::internals::AutoName, serialized, $input);
@@ -5,6 +5,7 @@ use crate::parser::Rule;
 use super::position::Position;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct TextNode {
     pub value: String,
     pub position: Position,
@@ -28,30 +29,82 @@ impl TextNode {
         )
     }
 
+    pub fn unescaped_from_pair(
+        pair: &pest::iterators::Pair<Rule>,
+        position_offset: Option<usize>,
+    ) -> Self {
+        let mut text_node = Self::from_pair(pair, position_offset);
+        text_node.unescape();
+        text_node
+    }
+
+    /// Builds a node for `pair` without its outer brackets, e.g. `[title]` becomes `title`.
     pub fn without_brackets(
         pair: &pest::iterators::Pair<Rule>,
         position_offset: Option<usize>,
     ) -> Self {
         let mut text_node = Self::from_pair(pair, position_offset);
-        let new_value = text_node
-            .value
-            .trim_matches(|c| c == '{' || c == '}' || c == '[' || c == ']')
-            .to_string();
-        if new_value == text_node.value {
+        let is_bracket = |c| c == '{' || c == '}' || c == '[' || c == ']';
+        let value = text_node.value.as_str();
+        // Only the outer pair is stripped, so an escaped `\}` right before the closing bracket survives
+        if value.len() < 2 || !value.starts_with(is_bracket) || !value.ends_with(is_bracket) {
             return text_node; // No change needed
         }
-        text_node.value = new_value;
+        text_node.value = value[1..value.len() - 1].to_string();
+        text_node.source = text_node.value.clone();
 
         text_node.position.shift_start(1); // Adjust position to account for removed brackets
         text_node.position.shift_end_down(1); // Adjust end position as well
         text_node
     }
 
+    /// Strips `to_strip` from the start of the value when it is there.
     pub fn trim_content_start(&mut self, to_strip: &str) {
         if self.value.starts_with(to_strip) {
-            self.value = self.value.trim_start_matches(to_strip).to_string();
-            self.position.shift_start(to_strip.len());
+            self.strip_start(to_strip.len());
+        }
+    }
+
+    /// Drops the first `len` bytes of the value and source, keeping the two in step with the position.
+    fn strip_start(&mut self, len: usize) {
+        self.value = self.value[len..].to_string();
+        self.source = self.source[len..].to_string();
+        self.position.shift_start(len);
+    }
+
+    /// Replaces the escape sequences `\@`, `\{` and `\}` in the value with the literal characters.
+    /// The `source` and position keep pointing at the escaped text.
+    pub fn unescape(&mut self) {
+        if self.value.contains('\\') {
+            self.value = unescape(&self.value);
+        }
+    }
+
+    /// Returns a node for the `start..end` byte range of this node's value,
+    /// positioned relative to this node.
+    pub fn slice(&self, start: usize, end: usize) -> TextNode {
+        let value = &self.value[start..end];
+        let position_start = self.position.start + start;
+        TextNode::new(
+            value.to_string(),
+            Position::new(position_start, position_start + value.len(), None),
+            value.to_string(),
+        )
+    }
+
+    /// Prompts are kept as `TextNode`s for liquid-html-parser parity.
+    /// Returns the raw text after `@prompt` when this node is one.
+    pub fn prompt_content(&self) -> Option<TextNode> {
+        let rest = self.source.strip_prefix(PROMPT_TAG)?;
+        if rest.starts_with(is_identifier_character) {
+            return None;
         }
+        let start = self.position.start + PROMPT_TAG.len();
+        Some(TextNode::new(
+            rest.to_string(),
+            Position::new(start, start + rest.len(), None),
+            rest.to_string(),
+        ))
     }
 
     #[allow(dead_code)]
@@ -62,15 +115,96 @@ impl TextNode {
     pub fn is_empty(&self) -> bool {
         self.value.is_empty()
     }
+
+    pub fn normalize_line_endings(&mut self) {
+        if self.value.contains('\r') {
+            self.value = normalize_line_endings(&self.value);
+        }
+    }
+}
+
+/// Returns the length of the line break at the start of `s`, if there is one.
+pub(crate) fn line_break_len(s: &str) -> Option<usize> {
+    if s.starts_with("\r\n") {
+        Some(2)
+    } else if s.starts_with('\n') || s.starts_with('\r') {
+        Some(1)
+    } else {
+        None
+    }
+}
+
+/// Returns the byte index and length of the first line break in `s`, if there is one.
+pub(crate) fn find_line_break(s: &str) -> Option<(usize, usize)> {
+    let index = s.find(['\r', '\n'])?;
+    line_break_len(&s[index..]).map(|len| (index, len))
+}
+
+const PROMPT_TAG: &str = "@prompt";
+
+/// Characters that can be escaped with a backslash in LiquidDoc text.
+const ESCAPABLE: [char; 3] = ['@', '{', '}'];
+
+/// Replaces `\@`, `\{` and `\}` with the literal characters.
+pub(crate) fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match chars.peek() {
+            Some(&next) if c == '\\' && ESCAPABLE.contains(&next) => {}
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// Escapes every `@` that would otherwise start a tag, e.g. `@media` becomes `\@media`.
+/// Use it when printing a text value back into a doc block.
+pub(crate) fn escape_text(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '@'
+            && chars
+                .peek()
+                .is_some_and(|&next| is_identifier_character(next))
+        {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Escapes the braces in a param type, e.g. `{a}` becomes `\{a\}`.
+pub(crate) fn escape_type(s: &str) -> String {
+    s.replace('{', "\\{").replace('}', "\\}")
+}
+
+pub(crate) fn is_identifier_character(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+/// Rewrites `\r\n` and lone `\r` line endings to `\n`.
+pub(crate) fn normalize_line_endings(s: &str) -> String {
+    s.replace("\r\n", "\n").replace('\r', "\n")
 }
 
 #[cfg(test)]
 mod tests {
+    use super::{escape_text, escape_type, unescape, Position, TextNode};
     use crate::assert_json_output;
+    use crate::parser::ParseOptions;
+    use pretty_assertions::assert_eq;
 
     #[test]
     fn fallback_node() {
-        assert_json_output!("@unsupported this node falls back to a text node")
+        assert_json_output!(
+            "@unsupported this node falls back to a text node",
+            ParseOptions::new()
+                .with_position_offset(Some(10))
+                .with_fallback_text_nodes(true)
+        )
     }
 
     #[test]
@@ -78,10 +212,62 @@ mod tests {
         assert_json_output!("@prompt Make something pretty")
     }
 
+    #[test]
+    fn trim_content_start_keeps_the_position_in_step() {
+        let input = "- The title";
+        let mut node = TextNode::new(
+            input.to_string(),
+            Position::new(4, 4 + input.len(), None),
+            input.to_string(),
+        );
+        node.trim_content_start("- ");
+        assert_eq!(node.value, "The title");
+        assert_eq!(node.source, "The title");
+        assert_eq!(node.position.start, 6);
+
+        node.trim_content_start("- ");
+        assert_eq!(node.value, "The title");
+    }
+
+    #[test]
+    fn normalize_line_endings_keeps_positions() {
+        let input = "first\r\nsecond\rthird";
+        let mut node = TextNode::new(
+            input.to_string(),
+            Position::new(0, input.len(), Some(10)),
+            input.to_string(),
+        );
+        node.normalize_line_endings();
+        assert_eq!(node.value, "first\nsecond\nthird");
+        assert_eq!(node.source, input);
+        assert_eq!(node.position, Position::new(0, input.len(), Some(10)));
+    }
+
+    #[test]
+    fn escape_round_trip() {
+        let value = "Uses @media queries, email me@example.com";
+        let escaped = escape_text(value);
+        assert_eq!(escaped, "Uses \\@media queries, email me\\@example.com");
+        assert_eq!(unescape(&escaped), value);
+        assert_eq!(unescape(&escape_type("{a: string}")), "{a: string}");
+    }
+
+    #[test]
+    fn escaped_at_sign_does_not_start_a_tag() {
+        assert_json_output!("@prompt Styles with \\@media queries\n@param {string} title");
+    }
+
+    #[test]
+    fn escaped_text_node() {
+        assert_json_output!("@param {string} title\ncontact support\\@example.com for help");
+    }
+
     #[test]
     pub fn test_multiline_with_prompt_tag() {
-        assert_json_output!("@prompt
+        assert_json_output!(
+            "@prompt
 This is a prompt
-It can have multiple lines");
+It can have multiple lines"
+        );
     }
 }
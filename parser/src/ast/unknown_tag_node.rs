@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+
+use crate::events::node_from_pair;
+use crate::parser::{ParseOptions, Rule};
+
+use super::{position::Position, LiquidNode, TextNode};
+
+/// A tag the parser does not support, e.g. `@unsupported some content`.
+/// `name` holds the tag name without the leading `@`, and `tagName` carries its own span
+/// so tooling can point at (or replace) exactly the misspelled or unknown tag.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct LiquidDocUnknownTagNode {
+    pub name: String,
+    pub position: Position,
+    pub source: String,
+    #[serde(rename = "tagName")]
+    pub tag_name: Box<LiquidNode>,
+    pub content: Box<LiquidNode>,
+}
+
+impl LiquidDocUnknownTagNode {
+    pub(crate) fn new(
+        tag_name: TextNode,
+        content: TextNode,
+        position: Position,
+        source: String,
+    ) -> Self {
+        LiquidDocUnknownTagNode {
+            name: tag_name.value.clone(),
+            position,
+            source,
+            tag_name: Box::new(LiquidNode::TextNode(tag_name)),
+            content: Box::new(LiquidNode::TextNode(content)),
+        }
+    }
+
+    pub fn from_pair(pair: &pest::iterators::Pair<Rule>, position_offset: Option<usize>) -> Self {
+        assert!(
+            pair.as_rule() == Rule::FallbackNode,
+            "Expected a FallbackNode, found {:?}",
+            pair.as_rule()
+        );
+
+        let options = ParseOptions::new().with_position_offset(position_offset);
+        match node_from_pair(pair.clone(), &options) {
+            Some(LiquidNode::LiquidDocUnknownTagNode(node)) => node,
+            _ => unreachable!("Expected a FallbackNode to build an unknown tag"),
+        }
+    }
+
+    pub fn tag_name(&self) -> &TextNode {
+        match self.tag_name.as_ref() {
+            LiquidNode::TextNode(text_node) => text_node,
+            _ => unreachable!("Expected tagName to be a TextNode"),
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        match self.content.as_ref() {
+            LiquidNode::TextNode(text_node) => text_node.value.as_str(),
+            _ => unreachable!("Expected content to be a TextNode"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_json_output, ast::LiquidNode, parser::parse_liquid_string};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_unknown_tag_node() {
+        let input = "@unsupported this node is not a known tag";
+        let node = parse_liquid_string(input, Some(10)).unwrap().head();
+
+        if let LiquidNode::LiquidDocUnknownTagNode(unknown_tag) = node {
+            assert_eq!(unknown_tag.name, "unsupported");
+            assert_eq!(unknown_tag.tag_name().as_str(), "unsupported");
+            assert_eq!(unknown_tag.tag_name().position.start, 11);
+            assert_eq!(unknown_tag.tag_name().position.end, 22);
+            assert_eq!(unknown_tag.value(), "this node is not a known tag");
+        } else {
+            panic!("Expected a LiquidDocUnknownTagNode");
+        }
+    }
+
+    #[test]
+    fn implicit_description_starting_with_at_sign_is_kept() {
+        let input = "@ the start of a description";
+        let node = parse_liquid_string(input, None).unwrap().head();
+
+        assert!(matches!(node, LiquidNode::LiquidDocDescriptionNode(_)));
+    }
+
+    #[test]
+    fn unknown_tag_node() {
+        assert_json_output!("@unsupported this node is not a known tag");
+    }
+
+    #[test]
+    fn unknown_tag_without_content() {
+        assert_json_output!("@todo\n@param {string} title");
+    }
+}
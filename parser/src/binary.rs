@@ -0,0 +1,104 @@
+use std::fmt;
+
+use crate::ast::LiquidAST;
+
+/// Starts every encoded AST, so other data is rejected before decoding.
+pub const BINARY_MAGIC: &[u8; 4] = b"LDOC";
+
+/// Version of the binary framing and encoding. Bump it whenever either changes; the shape of
+/// the nodes inside is versioned separately by `AST_VERSION`.
+pub const BINARY_FORMAT_VERSION: u16 = 1;
+
+const HEADER_LEN: usize = BINARY_MAGIC.len() + 2;
+
+#[derive(Debug)]
+pub enum BinaryError {
+    /// The bytes do not start with [`BINARY_MAGIC`].
+    InvalidHeader,
+    UnsupportedVersion(u16),
+    Encode(rmp_serde::encode::Error),
+    Decode(rmp_serde::decode::Error),
+}
+
+impl fmt::Display for BinaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BinaryError::InvalidHeader => write!(f, "Not a binary LiquidAST"),
+            BinaryError::UnsupportedVersion(version) => write!(
+                f,
+                "Unsupported binary format version {}, expected {}",
+                version, BINARY_FORMAT_VERSION
+            ),
+            BinaryError::Encode(error) => write!(f, "Could not encode the LiquidAST: {}", error),
+            BinaryError::Decode(error) => write!(f, "Could not decode the LiquidAST: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for BinaryError {}
+
+/// Encodes `ast` as MessagePack behind a header of [`BINARY_MAGIC`] and the little-endian
+/// [`BINARY_FORMAT_VERSION`].
+///
+/// Structs are written as maps, since the `type` tags of the root and of `LiquidNode` only
+/// round-trip through named fields, and a self-describing format is needed for them at all.
+/// Strings, numbers and booleans are still much smaller and faster to read than in JSON.
+///
+/// ```
+/// use liquid_doc_parser::{from_binary, parse_liquid_string, to_binary};
+///
+/// let ast = parse_liquid_string("@param {string} title", None).unwrap();
+/// let bytes = to_binary(&ast).unwrap();
+/// assert_eq!(from_binary(&bytes).unwrap(), ast);
+/// ```
+pub fn to_binary(ast: &LiquidAST) -> Result<Vec<u8>, BinaryError> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN);
+    bytes.extend_from_slice(BINARY_MAGIC);
+    bytes.extend_from_slice(&BINARY_FORMAT_VERSION.to_le_bytes());
+    rmp_serde::encode::write_named(&mut bytes, ast).map_err(BinaryError::Encode)?;
+    Ok(bytes)
+}
+
+pub fn from_binary(bytes: &[u8]) -> Result<LiquidAST, BinaryError> {
+    if bytes.len() < HEADER_LEN || !bytes.starts_with(BINARY_MAGIC) {
+        return Err(BinaryError::InvalidHeader);
+    }
+
+    let version = u16::from_le_bytes([bytes[BINARY_MAGIC.len()], bytes[BINARY_MAGIC.len() + 1]]);
+    if version != BINARY_FORMAT_VERSION {
+        return Err(BinaryError::UnsupportedVersion(version));
+    }
+
+    rmp_serde::from_slice(&bytes[HEADER_LEN..]).map_err(BinaryError::Decode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_liquid_string;
+
+    #[test]
+    fn rejects_other_data_and_versions() {
+        assert!(matches!(
+            from_binary(b"{\"type\":\"LiquidDoc\"}"),
+            Err(BinaryError::InvalidHeader)
+        ));
+
+        let mut bytes = to_binary(&LiquidAST::new()).unwrap();
+        bytes[BINARY_MAGIC.len()] = 2;
+        assert!(matches!(
+            from_binary(&bytes),
+            Err(BinaryError::UnsupportedVersion(2))
+        ));
+    }
+
+    #[test]
+    fn binary_is_smaller_than_json() {
+        let input = include_str!("../../web/fixtures/fixture1.liquid");
+        let ast = parse_liquid_string(input, None).unwrap();
+
+        let binary = to_binary(&ast).unwrap();
+        let json = serde_json::to_vec(&ast).unwrap();
+        assert!(binary.len() < json.len());
+    }
+}
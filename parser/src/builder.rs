@@ -0,0 +1,568 @@
+use std::fmt;
+
+use crate::ast::{escape_text, escape_type, is_identifier_character, LiquidAST};
+use crate::parser::parse_liquid_string;
+
+const FENCE: &str = "```";
+
+/// Something [`DocBuilder`] was given that LiquidDoc cannot represent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocBuilderError {
+    /// A param name that is empty or has characters other than letters, digits, `_` and `-`.
+    InvalidParamName(String),
+    /// A param type that is empty or has whitespace or a `\`, e.g. `string | number`.
+    InvalidParamType { name: String, param_type: String },
+    /// A param description spanning several lines.
+    MultilineParamDescription(String),
+    /// An example title spanning several lines.
+    MultilineExampleTitle(String),
+    /// An example language that is not a single word.
+    InvalidExampleLanguage(String),
+    /// Fenced example code containing a fence, which would close it early.
+    FenceInExampleCode,
+    /// Unfenced example code starting with a fence, which would parse as fenced code.
+    UnfencedExampleCodeStartsWithFence,
+    /// A titled example without code, whose title would parse as the example's content.
+    ExampleTitleWithoutCode(String),
+    /// The printed text did not parse.
+    Unparseable,
+}
+
+impl fmt::Display for DocBuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DocBuilderError::InvalidParamName(name) => write!(f, "Invalid param name `{}`", name),
+            DocBuilderError::InvalidParamType { name, param_type } => {
+                write!(f, "Invalid type `{}` for param `{}`", param_type, name)
+            }
+            DocBuilderError::MultilineParamDescription(name) => {
+                write!(f, "The description of param `{}` spans several lines", name)
+            }
+            DocBuilderError::MultilineExampleTitle(title) => {
+                write!(f, "The example title `{}` spans several lines", title)
+            }
+            DocBuilderError::InvalidExampleLanguage(language) => {
+                write!(f, "Invalid example language `{}`", language)
+            }
+            DocBuilderError::FenceInExampleCode => {
+                write!(f, "Fenced example code cannot contain {}", FENCE)
+            }
+            DocBuilderError::UnfencedExampleCodeStartsWithFence => {
+                write!(
+                    f,
+                    "Example code without a language cannot start with {}",
+                    FENCE
+                )
+            }
+            DocBuilderError::ExampleTitleWithoutCode(title) => {
+                write!(f, "The example titled `{}` has no code", title)
+            }
+            DocBuilderError::Unparseable => write!(f, "The printed LiquidDoc did not parse"),
+        }
+    }
+}
+
+impl std::error::Error for DocBuilderError {}
+
+/// Whether `name` can follow `@param`: letters, digits, `_` and `-` only.
+pub(crate) fn is_param_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(is_identifier_character)
+}
+
+/// Whether `param_type` can go between the braces of a `@param`, which end at whitespace.
+pub(crate) fn is_param_type(param_type: &str) -> bool {
+    !param_type.is_empty() && !param_type.contains(|c: char| c.is_whitespace() || c == '\\')
+}
+
+/// A `@param` for [`DocBuilder`]. Params are required unless marked [`Param::optional`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Param {
+    name: String,
+    param_type: Option<String>,
+    required: bool,
+    description: Option<String>,
+}
+
+impl Param {
+    pub fn new(name: &str) -> Self {
+        Param {
+            name: name.to_string(),
+            param_type: None,
+            required: true,
+            description: None,
+        }
+    }
+
+    pub fn ty(mut self, param_type: &str) -> Self {
+        self.param_type = Some(param_type.to_string());
+        self
+    }
+
+    pub fn optional(mut self) -> Self {
+        self.required = false;
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    fn validate(&self) -> Result<(), DocBuilderError> {
+        if !is_param_name(&self.name) {
+            return Err(DocBuilderError::InvalidParamName(self.name.clone()));
+        }
+        if let Some(param_type) = &self.param_type {
+            if !is_param_type(param_type) {
+                return Err(DocBuilderError::InvalidParamType {
+                    name: self.name.clone(),
+                    param_type: param_type.clone(),
+                });
+            }
+        }
+        if self
+            .description
+            .as_ref()
+            .is_some_and(|description| description.contains(['\n', '\r']))
+        {
+            return Err(DocBuilderError::MultilineParamDescription(
+                self.name.clone(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn print(&self) -> String {
+        let mut line = "@param".to_string();
+        if let Some(param_type) = &self.param_type {
+            line.push_str(&format!(" {{{}}}", escape_type(param_type)));
+        }
+        if self.required {
+            line.push_str(&format!(" {}", self.name));
+        } else {
+            line.push_str(&format!(" [{}]", self.name));
+        }
+        if let Some(description) = &self.description {
+            line.push_str(&format!(" - {}", escape_text(description)));
+        }
+        line
+    }
+}
+
+/// An `@example` for [`DocBuilder`]. With a language, the code is printed in a Markdown fence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    code: String,
+    title: Option<String>,
+    language: Option<String>,
+}
+
+impl Example {
+    pub fn new(code: &str) -> Self {
+        Example {
+            code: code.to_string(),
+            title: None,
+            language: None,
+        }
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    pub fn language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    fn validate(&self) -> Result<(), DocBuilderError> {
+        if let Some(title) = self.title.as_ref().filter(|t| t.contains(['\n', '\r'])) {
+            return Err(DocBuilderError::MultilineExampleTitle(title.clone()));
+        }
+        if let Some(language) = &self.language {
+            if language.is_empty() || language.contains(char::is_whitespace) {
+                return Err(DocBuilderError::InvalidExampleLanguage(language.clone()));
+            }
+            if self.code.contains(FENCE) {
+                return Err(DocBuilderError::FenceInExampleCode);
+            }
+        } else {
+            if self.code.trim_start().starts_with(FENCE) {
+                return Err(DocBuilderError::UnfencedExampleCodeStartsWithFence);
+            }
+            // The parser only splits off a title when a body follows it
+            if let Some(title) = self.title.as_ref().filter(|_| self.code.trim().is_empty()) {
+                return Err(DocBuilderError::ExampleTitleWithoutCode(title.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    fn print(&self) -> String {
+        let mut text = "@example".to_string();
+        if let Some(title) = &self.title {
+            text.push_str(&format!(" {}", escape_text(title)));
+        }
+        text.push('\n');
+        match &self.language {
+            // Fenced code is taken verbatim, so it is not escaped
+            Some(language) => {
+                text.push_str(&format!("{}{}\n{}\n{}", FENCE, language, self.code, FENCE))
+            }
+            None => text.push_str(&escape_text(&self.code)),
+        }
+        text
+    }
+}
+
+/// Builds LiquidDoc content without hand-writing positions or source.
+///
+/// The doc is printed in the layout of Dawn's docs and parsed back, so every position in the
+/// built AST points into the printed text.
+///
+/// ```
+/// use liquid_doc_parser::{DocBuilder, LiquidDoc, Param};
+///
+/// let builder = DocBuilder::new()
+///     .description("Renders a card")
+///     .param(Param::new("title").ty("string").optional());
+/// assert_eq!(builder.to_text().unwrap(), "Renders a card\n\n@param {string} [title]");
+///
+/// let doc = LiquidDoc::from_ast(&builder.build().unwrap());
+/// assert!(!doc.param("title").unwrap().required);
+///
+/// // Names and types LiquidDoc cannot represent are rejected rather than misprinted
+/// assert!(DocBuilder::new().param(Param::new("my title")).build().is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocBuilder {
+    description: Option<String>,
+    params: Vec<Param>,
+    examples: Vec<Example>,
+    prompts: Vec<String>,
+}
+
+impl DocBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the description, printed as the doc's first paragraph.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    pub fn param(mut self, param: Param) -> Self {
+        self.params.push(param);
+        self
+    }
+
+    pub fn example(mut self, example: Example) -> Self {
+        self.examples.push(example);
+        self
+    }
+
+    pub fn prompt(mut self, prompt: &str) -> Self {
+        self.prompts.push(prompt.to_string());
+        self
+    }
+
+    /// The doc's text, as it goes between `{% doc %}` and `{% enddoc %}`.
+    pub fn to_text(&self) -> Result<String, DocBuilderError> {
+        for param in &self.params {
+            param.validate()?;
+        }
+        for example in &self.examples {
+            example.validate()?;
+        }
+
+        let mut sections = Vec::new();
+        if let Some(description) = &self.description {
+            sections.push(escape_text(description));
+        }
+        if !self.params.is_empty() {
+            let params: Vec<String> = self.params.iter().map(Param::print).collect();
+            sections.push(params.join("\n"));
+        }
+        sections.extend(self.examples.iter().map(Example::print));
+        sections.extend(
+            self.prompts
+                .iter()
+                .map(|prompt| format!("@prompt\n{}", escape_text(prompt))),
+        );
+        Ok(sections.join("\n\n"))
+    }
+
+    /// The doc's text in a `{% doc %}` block, indented by two spaces as in Dawn's snippets.
+    pub fn to_doc_block(&self) -> Result<String, DocBuilderError> {
+        let lines: Vec<String> = self
+            .to_text()?
+            .lines()
+            .map(|line| match line {
+                "" => String::new(),
+                line => format!("  {}", line),
+            })
+            .collect();
+        Ok(format!(
+            "{{% doc %}}\n{}\n{{% enddoc %}}\n",
+            lines.join("\n")
+        ))
+    }
+
+    /// Parses the printed text, with positions relative to its start.
+    pub fn build(&self) -> Result<LiquidAST, DocBuilderError> {
+        self.build_with_offset(None)
+    }
+
+    /// Like [`DocBuilder::build`], shifted as if the text followed `{% doc %}` at `position_offset`.
+    pub fn build_with_offset(
+        &self,
+        position_offset: Option<usize>,
+    ) -> Result<LiquidAST, DocBuilderError> {
+        parse_liquid_string(&self.to_text()?, position_offset).ok_or(DocBuilderError::Unparseable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LiquidDoc, ParamType};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn prints_in_the_dawn_layout() {
+        let builder = DocBuilder::new()
+            .description("Renders a card")
+            .param(Param::new("title").ty("string").description("The title"))
+            .param(Param::new("width").ty("number").optional())
+            .example(Example::new("{% render 'card', title: 'Hat' %}"))
+            .prompt("Write a card");
+
+        assert_eq!(
+            builder.to_text().unwrap(),
+            "Renders a card\n\n@param {string} title - The title\n@param {number} [width]\n\n@example\n{% render 'card', title: 'Hat' %}\n\n@prompt\nWrite a card"
+        );
+    }
+
+    #[test]
+    fn built_ast_documents_what_was_given() {
+        let doc = LiquidDoc::from_ast(
+            &DocBuilder::new()
+                .description("Renders a card")
+                .param(Param::new("title").ty("string").description("The title"))
+                .param(Param::new("images").ty("image[]").optional())
+                .example(
+                    Example::new("{% render 'card' %}")
+                        .title("Basic card")
+                        .language("liquid"),
+                )
+                .prompt("Write a card")
+                .build()
+                .unwrap(),
+        );
+
+        assert_eq!(doc.description_text(), Some("Renders a card"));
+        let title = doc.param("title").unwrap();
+        assert!(title.required);
+        assert_eq!(title.param_type, Some(ParamType::String));
+        assert_eq!(title.description.as_deref(), Some("The title"));
+        assert_eq!(
+            doc.param("images").unwrap().param_type,
+            Some(ParamType::Array(Some(Box::new(ParamType::Named(
+                "image".to_string()
+            )))))
+        );
+        assert_eq!(doc.examples[0].title.as_deref(), Some("Basic card"));
+        assert_eq!(doc.examples[0].language.as_deref(), Some("liquid"));
+        assert_eq!(doc.examples[0].code_or_content(), "{% render 'card' %}");
+        assert_eq!(doc.prompts[0].content, "Write a card");
+        assert!(doc.unknown_tags.is_empty());
+    }
+
+    #[test]
+    fn escapes_text_that_would_start_a_tag() {
+        let builder = DocBuilder::new()
+            .description("@media queries are supported")
+            .param(
+                Param::new("query")
+                    .ty("{min:number}")
+                    .description("An @media query"),
+            );
+        let doc = LiquidDoc::from_ast(&builder.build().unwrap());
+
+        assert_eq!(doc.description_text(), Some("@media queries are supported"));
+        let query = doc.param("query").unwrap();
+        assert_eq!(query.description.as_deref(), Some("An @media query"));
+        assert_eq!(
+            query.param_type,
+            Some(ParamType::Named("{min:number}".to_string()))
+        );
+    }
+
+    /// Asserts that the doc parsed back from `builder` documents exactly what it was given.
+    fn assert_round_trip(builder: &DocBuilder) {
+        let doc = LiquidDoc::from_ast(&builder.build().unwrap());
+
+        assert_eq!(doc.description_text(), builder.description.as_deref());
+        let params: Vec<_> = doc
+            .params
+            .values()
+            .map(|param| {
+                (
+                    param.name.clone(),
+                    param.param_type.clone(),
+                    param.required,
+                    param.description.clone(),
+                )
+            })
+            .collect();
+        let expected: Vec<_> = builder
+            .params
+            .iter()
+            .map(|param| {
+                (
+                    param.name.clone(),
+                    param.param_type.as_deref().map(ParamType::parse),
+                    param.required,
+                    param.description.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(params, expected);
+        let examples: Vec<_> = doc
+            .examples
+            .iter()
+            .map(|example| {
+                // Unfenced content starts with the title line
+                let body = match (&example.code, &example.title) {
+                    (None, Some(_)) => example
+                        .content
+                        .split_once('\n')
+                        .map_or("", |(_, body)| body),
+                    _ => example.code_or_content(),
+                };
+                (
+                    // Content runs up to the next tag, blank lines included
+                    body.trim_end().to_string(),
+                    example.title.clone(),
+                    example.language.clone(),
+                )
+            })
+            .collect();
+        let expected: Vec<_> = builder
+            .examples
+            .iter()
+            .map(|example| {
+                (
+                    example.code.clone(),
+                    example.title.clone(),
+                    example.language.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(examples, expected);
+        let prompts: Vec<_> = doc.prompts.iter().map(|p| p.content.clone()).collect();
+        assert_eq!(prompts, builder.prompts);
+        assert!(doc.unknown_tags.is_empty());
+    }
+
+    #[test]
+    fn reparsed_docs_match_the_builder_input() {
+        assert_round_trip(
+            &DocBuilder::new()
+                .description("Renders a card\nwith an @image")
+                .param(Param::new("title").ty("string").description("The title"))
+                .param(Param::new("image-url").ty("{url:string}").optional())
+                .param(Param::new("product_2"))
+                .param(
+                    Param::new("items")
+                        .ty("product[]")
+                        .description("@see products"),
+                )
+                .example(Example::new("{% render 'card' %}"))
+                .example(
+                    Example::new("@param {string} title\n{% render 'card' %}")
+                        .title("Fenced")
+                        .language("liquid"),
+                )
+                .prompt("Write a card"),
+        );
+        assert_round_trip(&DocBuilder::new().param(Param::new("title")));
+        assert_round_trip(&DocBuilder::new());
+        assert_round_trip(
+            &DocBuilder::new()
+                .example(Example::new(""))
+                .example(Example::new("").title("Empty").language("liquid"))
+                .example(Example::new("Use\n```liquid\n{{ title }}\n```").title("Nested")),
+        );
+    }
+
+    #[test]
+    fn rejects_what_liquid_doc_cannot_represent() {
+        let error = |builder: DocBuilder| builder.build().unwrap_err();
+
+        assert_eq!(
+            error(DocBuilder::new().param(Param::new("my title"))),
+            DocBuilderError::InvalidParamName("my title".to_string())
+        );
+        assert_eq!(
+            error(DocBuilder::new().param(Param::new(""))),
+            DocBuilderError::InvalidParamName(String::new())
+        );
+        assert_eq!(
+            error(DocBuilder::new().param(Param::new("ok?"))),
+            DocBuilderError::InvalidParamName("ok?".to_string())
+        );
+        assert_eq!(
+            error(DocBuilder::new().param(Param::new("title").ty("string | number"))),
+            DocBuilderError::InvalidParamType {
+                name: "title".to_string(),
+                param_type: "string | number".to_string(),
+            }
+        );
+        assert_eq!(
+            error(DocBuilder::new().param(Param::new("title").description("The\n@param title"))),
+            DocBuilderError::MultilineParamDescription("title".to_string())
+        );
+        assert_eq!(
+            error(DocBuilder::new().example(Example::new("```\ncode\n```").language("md"))),
+            DocBuilderError::FenceInExampleCode
+        );
+        assert_eq!(
+            error(DocBuilder::new().example(Example::new("code").language("liquid html"))),
+            DocBuilderError::InvalidExampleLanguage("liquid html".to_string())
+        );
+        assert_eq!(
+            error(DocBuilder::new().example(Example::new("code").title("A\nB"))),
+            DocBuilderError::MultilineExampleTitle("A\nB".to_string())
+        );
+        assert_eq!(
+            error(DocBuilder::new().example(Example::new("").title("Empty"))),
+            DocBuilderError::ExampleTitleWithoutCode("Empty".to_string())
+        );
+        assert_eq!(
+            error(DocBuilder::new().example(Example::new("\n```\ncode\n```"))),
+            DocBuilderError::UnfencedExampleCodeStartsWithFence
+        );
+    }
+
+    #[test]
+    fn positions_point_into_the_printed_text() {
+        let builder = DocBuilder::new().param(Param::new("title").ty("string"));
+        let ast = builder.build_with_offset(Some(9)).unwrap();
+        let text = builder.to_text().unwrap();
+
+        assert_eq!(ast.position_offset, 9);
+        match &ast.nodes[0] {
+            crate::LiquidNode::LiquidDocParamNode(param) => {
+                assert_eq!(
+                    &text[param.position.start - 9..param.position.end - 9],
+                    "@param {string} title"
+                );
+            }
+            _ => panic!("Expected a LiquidDocParamNode"),
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use serde::Serialize;
+
+use crate::ast::Position;
+use crate::diagnostics::Diagnostic;
+use crate::liquid_tags::{tokenize, LiquidTags, TokenKind};
+use crate::render_calls::{check_arguments, parse_arguments, Argument, ArgumentValue};
+use crate::theme_index::{ThemeDiagnostic, ThemeFile, ThemeIndex};
+
+/// A `{% content_for %}` tag of a template.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ContentForCall {
+    /// The whole tag, or the line of a `{% liquid %}` tag.
+    pub position: Position,
+    /// `block` for a static block, `blocks` for the blocks merchants add.
+    pub kind: String,
+    /// The `type:` of a static block, when it is a string literal.
+    #[serde(rename = "blockType")]
+    pub block_type: Option<String>,
+    #[serde(rename = "blockTypePosition")]
+    pub block_type_position: Option<Position>,
+    /// The `id:` of a static block, when it is a string literal.
+    pub id: Option<String>,
+    /// Every argument but the reserved `type` and `id`, passed to the block.
+    pub arguments: Vec<Argument>,
+}
+
+/// Finds the `{% content_for %}` tags of a template, outside raw, comment and doc blocks and
+/// including the lines of `{% liquid %}` tags.
+///
+/// ```
+/// use liquid_doc_parser::parse_content_for_calls;
+///
+/// let calls = parse_content_for_calls("{% content_for 'block', type: 'slide', id: 'hero', speed: 3 %}");
+/// assert_eq!(calls[0].block_type.as_deref(), Some("slide"));
+/// assert_eq!(calls[0].arguments[0].name, "speed");
+/// ```
+pub fn parse_content_for_calls(source: &str) -> Vec<ContentForCall> {
+    LiquidTags::new(source)
+        .filter(|tag| tag.name == "content_for")
+        .filter_map(|tag| {
+            let tokens = tokenize(tag.markup, tag.markup_start);
+            let (kind, rest) = tokens.split_first()?;
+            let TokenKind::String(kind) = &kind.kind else {
+                return None;
+            };
+
+            let mut call = ContentForCall {
+                position: Position::new(tag.start, tag.end, None),
+                kind: kind.clone(),
+                block_type: None,
+                block_type_position: None,
+                id: None,
+                arguments: Vec::new(),
+            };
+            for argument in parse_arguments(rest) {
+                let literal = match &argument.value {
+                    ArgumentValue::String(value) => Some(value.clone()),
+                    _ => None,
+                };
+                match argument.name.as_str() {
+                    "type" => {
+                        call.block_type = literal;
+                        call.block_type_position = Some(argument.value_position);
+                    }
+                    "id" => call.id = literal,
+                    _ => call.arguments.push(argument),
+                }
+            }
+            Some(call)
+        })
+        .collect()
+}
+
+/// Checks every static block of the theme against the `@param`s of its block file.
+pub fn check_content_for_calls(index: &ThemeIndex) -> Vec<ThemeDiagnostic> {
+    index
+        .files()
+        .flat_map(|file| {
+            check_file_content_for_calls(index, file)
+                .into_iter()
+                .map(|diagnostic| ThemeDiagnostic {
+                    path: file.path.clone(),
+                    diagnostic,
+                })
+        })
+        .collect()
+}
+
+/// Checks the static blocks of one file, e.g. the one open in an editor.
+///
+/// `type` and `id` are never params, and `closest.*` arguments are left to Shopify. Blocks
+/// that are undocumented, missing, or whose `type` is not a string literal are not checked.
+pub fn check_file_content_for_calls(index: &ThemeIndex, file: &ThemeFile) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for call in parse_content_for_calls(&file.source) {
+        let Some(block_type) = call.block_type.as_deref().filter(|_| call.kind == "block") else {
+            continue;
+        };
+        let Some(doc) = index
+            .block(block_type, file)
+            .and_then(|block| block.doc.as_ref())
+        else {
+            continue;
+        };
+
+        let arguments: Vec<Argument> = call
+            .arguments
+            .into_iter()
+            .filter(|argument| !argument.name.starts_with("closest."))
+            .collect();
+        diagnostics.extend(check_arguments(
+            &format!("block '{}'", block_type),
+            doc,
+            &arguments,
+            &[],
+            call.position,
+        ));
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const SLIDE: &str = "{% doc %}\n  @param {string} heading\n  @param {number} [speed]\n{% enddoc %}\n<div {{ block.shopify_attributes }}></div>";
+
+    fn codes_at(template: &str) -> Vec<(String, &str)> {
+        let mut index = ThemeIndex::new("theme");
+        index.update_file("blocks/slide.liquid", SLIDE);
+        index.update_file("sections/main.liquid", template);
+
+        check_file_content_for_calls(&index, index.section("main").unwrap())
+            .into_iter()
+            .map(|diagnostic| {
+                let position = diagnostic.position;
+                (diagnostic.code, &template[position.start..position.end])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_static_blocks() {
+        let source = "{% content_for 'block', type: 'slide', id: 'hero', heading: 'Hi' %}{% content_for 'blocks' %}";
+        let calls = parse_content_for_calls(source);
+
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].id.as_deref(), Some("hero"));
+        let type_position = calls[0].block_type_position.unwrap();
+        assert_eq!(&source[type_position.start..type_position.end], "'slide'");
+        assert_eq!(calls[0].arguments.len(), 1);
+        assert_eq!(calls[1].kind, "blocks");
+        assert_eq!(calls[1].block_type, None);
+    }
+
+    #[test]
+    fn valid_blocks_have_no_diagnostics() {
+        assert_eq!(
+            codes_at("{% content_for 'block', type: 'slide', id: 'a', heading: section.settings.title, closest.product: product %}"),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn reports_missing_unknown_and_mismatched_arguments() {
+        let template =
+            "{% content_for 'block', id: 'a', type: 'slide', speed: 'fast', title: 'Hi' %}";
+
+        assert_eq!(
+            codes_at(template),
+            vec![
+                ("ArgumentTypeMismatch".to_string(), "'fast'"),
+                ("UnknownArgument".to_string(), "title: 'Hi'"),
+                ("MissingRequiredParam".to_string(), template),
+            ]
+        );
+    }
+
+    #[test]
+    fn extension_blocks_are_not_the_themes() {
+        let mut index = ThemeIndex::new("theme");
+        index.update_file("extensions/reviews/blocks/slide.liquid", SLIDE);
+        index.update_file(
+            "extensions/reviews/blocks/carousel.liquid",
+            "{% content_for 'block', type: 'slide', id: 'a', speed: 'fast' %}",
+        );
+        index.update_file(
+            "sections/main.liquid",
+            "{% content_for 'block', type: 'slide', id: 'a' %}",
+        );
+
+        let codes = |path: &str| -> Vec<String> {
+            check_file_content_for_calls(&index, index.file(path).unwrap())
+                .into_iter()
+                .map(|diagnostic| diagnostic.code)
+                .collect()
+        };
+        assert_eq!(
+            codes("extensions/reviews/blocks/carousel.liquid"),
+            vec![
+                "ArgumentTypeMismatch".to_string(),
+                "MissingRequiredParam".to_string()
+            ]
+        );
+        // The theme has no `slide` block of its own to check against
+        assert_eq!(codes("sections/main.liquid"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn skips_dynamic_and_unknown_blocks() {
+        assert_eq!(
+            codes_at("{% content_for 'blocks' %}{% content_for 'block', type: block_type, id: 'a' %}{% content_for 'block', type: 'missing', id: 'b' %}"),
+            vec![]
+        );
+    }
+}
@@ -0,0 +1,384 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::theme_index::{ThemeFile, ThemeFileKind, ThemeIndex};
+
+/// How much of a set of snippets and blocks is documented.
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct CoverageCounts {
+    pub files: usize,
+    /// Files with a `{% doc %}` block.
+    pub documented: usize,
+    /// Files whose doc has at least one `@example`.
+    #[serde(rename = "withExamples")]
+    pub with_examples: usize,
+    pub params: usize,
+    /// Params with a `{type}`.
+    #[serde(rename = "typedParams")]
+    pub typed_params: usize,
+    /// Params with a description.
+    #[serde(rename = "describedParams")]
+    pub described_params: usize,
+}
+
+impl CoverageCounts {
+    fn of(file: &ThemeFile) -> Self {
+        let mut counts = CoverageCounts {
+            files: 1,
+            ..CoverageCounts::default()
+        };
+        let Some(doc) = &file.doc else {
+            return counts;
+        };
+        counts.documented = 1;
+        counts.with_examples = usize::from(!doc.examples.is_empty());
+        for param in doc.params.values() {
+            counts.params += 1;
+            counts.typed_params += usize::from(param.param_type.is_some());
+            counts.described_params += usize::from(
+                param
+                    .description
+                    .as_deref()
+                    .is_some_and(|description| !description.trim().is_empty()),
+            );
+        }
+        counts
+    }
+
+    fn add(&mut self, other: &CoverageCounts) {
+        self.files += other.files;
+        self.documented += other.documented;
+        self.with_examples += other.with_examples;
+        self.params += other.params;
+        self.typed_params += other.typed_params;
+        self.described_params += other.described_params;
+    }
+
+    /// The share of checks passed, as an unrounded percentage. Every file counts once for having
+    /// a doc and once for having an example, and every param once for its type and once for its
+    /// description. With nothing to check, the score is 100.
+    pub fn score(&self) -> f64 {
+        percentage(
+            self.documented + self.with_examples + self.typed_params + self.described_params,
+            2 * (self.files + self.params),
+        )
+    }
+
+    /// The cells shared by the Markdown and terminal tables.
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.files.to_string(),
+            ratio(self.documented, self.files),
+            ratio(self.with_examples, self.files),
+            self.params.to_string(),
+            ratio(self.typed_params, self.params),
+            ratio(self.described_params, self.params),
+            format!("{:.1}%", self.score()),
+        ]
+    }
+}
+
+/// The coverage of one snippet or block.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct FileCoverage {
+    pub path: PathBuf,
+    pub kind: ThemeFileKind,
+    #[serde(flatten)]
+    pub counts: CoverageCounts,
+}
+
+/// The coverage of the snippets and blocks of one directory, e.g. `snippets`.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DirectoryCoverage {
+    pub directory: PathBuf,
+    #[serde(flatten)]
+    pub counts: CoverageCounts,
+}
+
+/// How much of a theme's snippets and blocks is documented, per file, per directory and overall.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct CoverageReport {
+    pub overall: CoverageCounts,
+    /// [`CoverageCounts::score`] of `overall`.
+    pub score: f64,
+    pub directories: Vec<DirectoryCoverage>,
+    pub files: Vec<FileCoverage>,
+}
+
+const HEADERS: [&str; 8] = [
+    "",
+    "Files",
+    "Documented",
+    "With examples",
+    "Params",
+    "Typed",
+    "Described",
+    "Score",
+];
+
+/// Measures the doc coverage of the snippets and blocks of a theme, the files rendered with
+/// arguments. Directories and files are ordered by path.
+///
+/// ```
+/// use liquid_doc_parser::{doc_coverage, ThemeIndex};
+///
+/// let mut index = ThemeIndex::new("theme");
+/// index.update_file("snippets/card.liquid", "{% doc %}@param {string} title - The title{% enddoc %}");
+/// index.update_file("snippets/legacy.liquid", "<div></div>");
+///
+/// let report = doc_coverage(&index);
+/// assert_eq!(report.overall.documented, 1);
+/// assert_eq!(report.overall.typed_params, 1);
+/// // 3 of the 6 checks pass: both files lack an example and one lacks a doc
+/// assert_eq!(report.score, 50.0);
+/// ```
+pub fn doc_coverage(index: &ThemeIndex) -> CoverageReport {
+    let mut overall = CoverageCounts::default();
+    let mut directories: BTreeMap<&Path, CoverageCounts> = BTreeMap::new();
+    let mut files = Vec::new();
+
+    for file in index
+        .files()
+        .filter(|file| matches!(file.kind, ThemeFileKind::Snippet | ThemeFileKind::Block))
+    {
+        let counts = CoverageCounts::of(file);
+        overall.add(&counts);
+        let directory = file.path.parent().unwrap_or(Path::new(""));
+        directories.entry(directory).or_default().add(&counts);
+        files.push(FileCoverage {
+            path: file.path.clone(),
+            kind: file.kind,
+            counts,
+        });
+    }
+
+    CoverageReport {
+        score: overall.score(),
+        overall,
+        directories: directories
+            .into_iter()
+            .map(|(directory, counts)| DirectoryCoverage {
+                directory: directory.to_path_buf(),
+                counts,
+            })
+            .collect(),
+        files,
+    }
+}
+
+impl CoverageReport {
+    /// Whether the overall score is at least `threshold` percent, before any rounding, so
+    /// 66.67% does not meet 66.7.
+    pub fn meets(&self, threshold: f64) -> bool {
+        self.score >= threshold
+    }
+
+    /// A Markdown summary per directory and overall, followed by a table of every file.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("# Documentation coverage\n\n");
+        markdown.push_str(&markdown_table(&HEADERS, &self.summary_rows()));
+
+        let file_headers = ["File", "Doc", "Examples", "Params", "Typed", "Described"];
+        let file_rows: Vec<Vec<String>> = self
+            .files
+            .iter()
+            .map(|file| {
+                let counts = &file.counts;
+                vec![
+                    format!("`{}`", file.path.to_string_lossy()),
+                    yes_no(counts.documented == 1),
+                    yes_no(counts.with_examples == 1),
+                    counts.params.to_string(),
+                    counts.typed_params.to_string(),
+                    counts.described_params.to_string(),
+                ]
+            })
+            .collect();
+        markdown.push_str("\n## Files\n\n");
+        markdown.push_str(&markdown_table(&file_headers, &file_rows));
+        markdown
+    }
+
+    /// A plain text table per directory and overall, with aligned columns for terminals.
+    pub fn to_table(&self) -> String {
+        let mut rows = vec![HEADERS.iter().map(|header| header.to_string()).collect()];
+        rows.extend(self.summary_rows());
+        let widths: Vec<usize> = (0..HEADERS.len())
+            .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap_or(0))
+            .collect();
+
+        let mut table = String::new();
+        for row in &rows {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(column, (cell, width))| match column {
+                    0 => format!("{:<width$}", cell, width = width),
+                    _ => format!("{:>width$}", cell, width = width),
+                })
+                .collect();
+            table.push_str(cells.join("  ").trim_end());
+            table.push('\n');
+        }
+        table
+    }
+
+    /// One row per directory, then the overall row.
+    fn summary_rows(&self) -> Vec<Vec<String>> {
+        let directories = self.directories.iter().map(|directory| {
+            (
+                directory.directory.to_string_lossy().into_owned(),
+                &directory.counts,
+            )
+        });
+        directories
+            .chain([("Overall".to_string(), &self.overall)])
+            .map(|(name, counts)| {
+                let mut row = vec![name];
+                row.extend(counts.cells());
+                row
+            })
+            .collect()
+    }
+}
+
+fn percentage(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 100.0;
+    }
+    part as f64 * 100.0 / total as f64
+}
+
+/// e.g. `2 (66.7%)`
+fn ratio(part: usize, total: usize) -> String {
+    format!("{} ({:.1}%)", part, percentage(part, total))
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "yes" } else { "no" }.to_string()
+}
+
+fn markdown_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut table = format!("| {} |\n", headers.join(" | "));
+    let alignments: Vec<&str> = (0..headers.len())
+        .map(|column| if column == 0 { "---" } else { "---:" })
+        .collect();
+    table.push_str(&format!("| {} |\n", alignments.join(" | ")));
+    for row in rows {
+        table.push_str(&format!("| {} |\n", row.join(" | ")));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn theme() -> ThemeIndex {
+        let mut index = ThemeIndex::new("theme");
+        index.update_file(
+            "snippets/card.liquid",
+            "{% doc %}\n  @param {string} title - The title\n  @param width\n\n  @example\n  {% render 'card', title: 'Hat' %}\n{% enddoc %}",
+        );
+        index.update_file("snippets/legacy.liquid", "<div></div>");
+        index.update_file(
+            "blocks/slide.liquid",
+            "{% doc %}\n  @param {string} heading\n{% enddoc %}",
+        );
+        index.update_file("sections/main.liquid", "<main></main>");
+        index
+    }
+
+    #[test]
+    fn counts_per_file_directory_and_overall() {
+        let report = doc_coverage(&theme());
+
+        assert_eq!(
+            report.overall,
+            CoverageCounts {
+                files: 3,
+                documented: 2,
+                with_examples: 1,
+                params: 3,
+                typed_params: 2,
+                described_params: 1,
+            }
+        );
+        // 6 of 12 checks
+        assert_eq!(report.score, 50.0);
+        assert_eq!(
+            report
+                .directories
+                .iter()
+                .map(|directory| (
+                    directory.directory.to_string_lossy(),
+                    directory.counts.files
+                ))
+                .collect::<Vec<_>>(),
+            vec![("blocks".into(), 1), ("snippets".into(), 2)]
+        );
+        assert_eq!(report.files[1].counts.typed_params, 1);
+        assert!(report.meets(50.0));
+        assert!(!report.meets(50.1));
+    }
+
+    #[test]
+    fn thresholds_compare_the_unrounded_score() {
+        let mut index = ThemeIndex::new("theme");
+        for name in ["a", "b"] {
+            index.update_file(
+                format!("snippets/{}.liquid", name),
+                "{% doc %}@example {% render 'a' %}{% enddoc %}",
+            );
+        }
+        index.update_file("snippets/c.liquid", "");
+        let report = doc_coverage(&index);
+
+        // 4 of 6 checks, shown as 66.7%
+        assert!(report.to_table().contains("66.7%"));
+        assert!(report.meets(66.6));
+        assert!(!report.meets(66.7));
+    }
+
+    #[test]
+    fn empty_themes_are_fully_covered() {
+        assert_eq!(doc_coverage(&ThemeIndex::new("theme")).score, 100.0);
+    }
+
+    #[test]
+    fn renders_markdown_and_terminal_tables() {
+        let report = doc_coverage(&theme());
+
+        assert_eq!(
+            report.to_markdown(),
+            r#"# Documentation coverage
+
+|  | Files | Documented | With examples | Params | Typed | Described | Score |
+| --- | ---: | ---: | ---: | ---: | ---: | ---: | ---: |
+| blocks | 1 | 1 (100.0%) | 0 (0.0%) | 1 | 1 (100.0%) | 0 (0.0%) | 50.0% |
+| snippets | 2 | 1 (50.0%) | 1 (50.0%) | 2 | 1 (50.0%) | 1 (50.0%) | 50.0% |
+| Overall | 3 | 2 (66.7%) | 1 (33.3%) | 3 | 2 (66.7%) | 1 (33.3%) | 50.0% |
+
+## Files
+
+| File | Doc | Examples | Params | Typed | Described |
+| --- | ---: | ---: | ---: | ---: | ---: |
+| `blocks/slide.liquid` | yes | no | 1 | 1 | 0 |
+| `snippets/card.liquid` | yes | yes | 2 | 1 | 1 |
+| `snippets/legacy.liquid` | no | no | 0 | 0 | 0 |
+"#
+        );
+        assert_eq!(
+            report.to_table(),
+            r#"          Files  Documented  With examples  Params       Typed  Described  Score
+blocks        1  1 (100.0%)       0 (0.0%)       1  1 (100.0%)   0 (0.0%)  50.0%
+snippets      2   1 (50.0%)      1 (50.0%)       2   1 (50.0%)  1 (50.0%)  50.0%
+Overall       3   2 (66.7%)      1 (33.3%)       3   2 (66.7%)  1 (33.3%)  50.0%
+"#
+        );
+    }
+}
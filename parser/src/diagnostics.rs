@@ -0,0 +1,322 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{LiquidAST, LiquidDocUnknownTagNode, LiquidNode, Position};
+
+/// The tags the parser understands, without the leading `@`.
+pub const BUILT_IN_TAGS: [&str; 4] = ["param", "example", "description", "prompt"];
+
+/// Unknown tags further than this from every known tag get no suggestion.
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// Replaces the text at `position` with `newText`. An empty position inserts the text.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub position: Position,
+    #[serde(rename = "newText")]
+    pub new_text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub code: String,
+    pub message: String,
+    pub severity: Severity,
+    pub position: Position,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<TextEdit>,
+}
+
+impl Diagnostic {
+    pub fn warning(code: &str, message: String, position: Position) -> Self {
+        Diagnostic {
+            code: code.to_string(),
+            message,
+            severity: Severity::Warning,
+            position,
+            suggestion: None,
+            fix: None,
+        }
+    }
+
+    pub fn error(code: &str, message: String, position: Position) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            ..Diagnostic::warning(code, message, position)
+        }
+    }
+
+    pub fn with_fix(mut self, suggestion: String, fix: TextEdit) -> Self {
+        self.suggestion = Some(suggestion);
+        self.fix = Some(fix);
+        self
+    }
+}
+
+/// Flags unknown tags, suggesting the closest built-in or registered custom tag.
+///
+/// ```
+/// use liquid_doc_parser::{parse_liquid_string, TagChecker};
+///
+/// let ast = parse_liquid_string("@parm {string} title", None).unwrap();
+/// let diagnostics = TagChecker::new().check(&ast);
+/// assert_eq!(diagnostics[0].suggestion.as_deref(), Some("param"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TagChecker {
+    custom_tags: Vec<String>,
+}
+
+impl TagChecker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a tag (without the leading `@`) that is valid in this project.
+    pub fn with_custom_tag(mut self, tag: &str) -> Self {
+        self.custom_tags.push(tag.to_string());
+        self
+    }
+
+    pub fn check(&self, ast: &LiquidAST) -> Vec<Diagnostic> {
+        ast.nodes
+            .iter()
+            .filter_map(|node| match node {
+                LiquidNode::LiquidDocUnknownTagNode(node) => self.check_unknown_tag(node),
+                LiquidNode::LiquidDocParamNode(node) => {
+                    check_param_spacing(node.position, &node.source)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn check_unknown_tag(&self, node: &LiquidDocUnknownTagNode) -> Option<Diagnostic> {
+        let tag_name = node.tag_name();
+        if self.custom_tags.iter().any(|tag| tag == &node.name) {
+            return None;
+        }
+        // A built-in tag only parses as unknown when its content does not fit, e.g. `@param`
+        // without a name, so suggesting the same tag would be no help
+        if BUILT_IN_TAGS.contains(&node.name.as_str()) {
+            return Some(Diagnostic::error(
+                "MalformedTag",
+                format!("Malformed `@{}`", node.name),
+                node.position,
+            ));
+        }
+
+        let diagnostic = Diagnostic::warning(
+            "UnknownTag",
+            format!("Unknown tag `@{}`", node.name),
+            tag_name.position,
+        );
+
+        match self.closest_tag(&node.name) {
+            Some(suggestion) => Some(
+                Diagnostic {
+                    message: format!(
+                        "Unknown tag `@{}`. Did you mean `@{}`?",
+                        node.name, suggestion
+                    ),
+                    ..diagnostic
+                }
+                .with_fix(
+                    suggestion.to_string(),
+                    TextEdit {
+                        position: tag_name.position,
+                        new_text: suggestion.to_string(),
+                    },
+                ),
+            ),
+            None => Some(diagnostic),
+        }
+    }
+
+    fn closest_tag(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        BUILT_IN_TAGS
+            .iter()
+            .copied()
+            .chain(self.custom_tags.iter().map(String::as_str))
+            .map(|tag| (tag, edit_distance(&name, tag)))
+            .filter(|&(tag, distance)| {
+                distance > 0 && distance <= MAX_SUGGESTION_DISTANCE && distance < tag.len()
+            })
+            .min_by_key(|&(_, distance)| distance)
+            .map(|(tag, _)| tag)
+    }
+}
+
+/// `@param{string} name` parses, but is almost always a missing space.
+fn check_param_spacing(position: Position, source: &str) -> Option<Diagnostic> {
+    const TAG: &str = "@param";
+    if !source.starts_with("@param{") {
+        return None;
+    }
+
+    let insert_at = position.start + TAG.len();
+    Some(
+        Diagnostic::warning(
+            "MissingSpaceAfterTag",
+            "Missing space between `@param` and its type".to_string(),
+            Position::new(position.start, insert_at + 1, None),
+        )
+        .with_fix(
+            "@param {".to_string(),
+            TextEdit {
+                position: Position::new(insert_at, insert_at, None),
+                new_text: " ".to_string(),
+            },
+        ),
+    )
+}
+
+/// Optimal string alignment distance: Levenshtein plus adjacent transpositions.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_liquid_string;
+    use pretty_assertions::assert_eq;
+
+    fn suggestions(input: &str, checker: &TagChecker) -> Vec<Option<String>> {
+        let ast = parse_liquid_string(input, None).unwrap();
+        checker
+            .check(&ast)
+            .into_iter()
+            .map(|diagnostic| diagnostic.suggestion)
+            .collect()
+    }
+
+    #[test]
+    fn suggests_built_in_tags_for_common_misspellings() {
+        let checker = TagChecker::new();
+        for (input, expected) in [
+            ("@parm {string} title", "param"),
+            ("@params {string} title", "param"),
+            ("@pram title", "param"),
+            ("@examples\n{% render 'card' %}", "example"),
+            ("@descripton Renders a card", "description"),
+            ("@promt Write a card", "prompt"),
+        ] {
+            assert_eq!(
+                suggestions(input, &checker),
+                vec![Some(expected.to_string())],
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn fix_replaces_the_tag_name() {
+        let input = "Renders a card\n@parm{string} title";
+        let ast = parse_liquid_string(input, Some(10)).unwrap();
+        let diagnostics = TagChecker::new().check(&ast);
+
+        assert_eq!(diagnostics.len(), 1);
+        let fix = diagnostics[0].fix.as_ref().unwrap();
+        assert_eq!(fix.new_text, "param");
+        assert_eq!(
+            &input[fix.position.start - 10..fix.position.end - 10],
+            "parm"
+        );
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn reports_unknown_tags_without_a_close_match() {
+        let ast = parse_liquid_string("@deprecated use card-v2", None).unwrap();
+        let diagnostics = TagChecker::new().check(&ast);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "Unknown tag `@deprecated`");
+        assert!(diagnostics[0].fix.is_none());
+    }
+
+    #[test]
+    fn malformed_built_in_tags_get_no_suggestion() {
+        for input in [
+            "@param {string | number} title",
+            "@param",
+            "@param {string}",
+        ] {
+            let ast = parse_liquid_string(input, None).unwrap();
+            let diagnostics = TagChecker::new().check(&ast);
+
+            assert_eq!(diagnostics.len(), 1, "{input}");
+            assert_eq!(diagnostics[0].code, "MalformedTag", "{input}");
+            assert_eq!(diagnostics[0].message, "Malformed `@param`", "{input}");
+            assert_eq!(diagnostics[0].severity, Severity::Error, "{input}");
+            assert_eq!(diagnostics[0].fix, None, "{input}");
+        }
+    }
+
+    #[test]
+    fn registered_custom_tags_are_known_and_suggested() {
+        let checker = TagChecker::new().with_custom_tag("deprecated");
+
+        assert_eq!(suggestions("@deprecated use card-v2", &checker), vec![]);
+        assert_eq!(
+            suggestions("@deprecatd use card-v2", &checker),
+            vec![Some("deprecated".to_string())]
+        );
+    }
+
+    #[test]
+    fn param_without_space_before_type() {
+        let input = "@param{string} title";
+        let ast = parse_liquid_string(input, None).unwrap();
+        let diagnostics = TagChecker::new().check(&ast);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "MissingSpaceAfterTag");
+        let fix = diagnostics[0].fix.as_ref().unwrap();
+        assert_eq!(fix.position, Position::new(6, 6, None));
+        assert_eq!(fix.new_text, " ");
+    }
+
+    #[test]
+    fn edit_distance_counts_transpositions_once() {
+        assert_eq!(edit_distance("param", "param"), 0);
+        assert_eq!(edit_distance("parm", "param"), 1);
+        assert_eq!(edit_distance("pram", "param"), 1);
+        assert_eq!(edit_distance("exmaple", "example"), 1);
+        assert_eq!(edit_distance("", "param"), 5);
+    }
+}
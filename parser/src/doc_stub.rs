@@ -0,0 +1,231 @@
+use serde::Serialize;
+
+use crate::ast::Position;
+use crate::builder::{is_param_name, DocBuilder, DocBuilderError, Param};
+use crate::diagnostics::TextEdit;
+use crate::liquid_doc::ParamType;
+use crate::liquid_file::parse_liquid_file;
+use crate::param_usage::{free_variables, UsageHint};
+
+const DESCRIPTION_TODO: &str = "TODO: Describe what this renders.";
+const PARAM_TODO: &str = "TODO";
+
+/// A `@param` inferred from the way a template reads a variable.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct StubParam {
+    pub name: String,
+    #[serde(rename = "paramType")]
+    pub param_type: ParamType,
+    pub required: bool,
+    /// Every hint given by the variable's reads, in the order first seen.
+    pub hints: Vec<UsageHint>,
+}
+
+/// The doc an undocumented template could start from.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DocStub {
+    /// One param per free variable, in the order they are first read.
+    pub params: Vec<StubParam>,
+    /// Free variables whose names a `@param` cannot have, e.g. `ok?`, so the stub leaves them out.
+    pub skipped: Vec<String>,
+}
+
+/// Infers a doc for a template without one from the variables its body reads.
+///
+/// A variable that is looped over or read as `.size` is an array, one passed to an arithmetic
+/// filter is a number, one read through a property is an object, and one only ever used as a
+/// condition is a boolean. Anything else is a string. Variables piped into `| default:` or
+/// used as conditions are optional.
+///
+/// Variables whose names a `@param` cannot have, such as `ok?`, are listed in
+/// [`DocStub::skipped`] instead.
+///
+/// Returns `None` when the template already has a `{% doc %}` block.
+///
+/// ```
+/// use liquid_doc_parser::infer_doc_stub;
+///
+/// let stub = infer_doc_stub("{% for item in items %}{{ item | times: scale }}{% endfor %}").unwrap();
+/// assert_eq!(
+///     stub.to_text().unwrap(),
+///     "{% doc %}\n  TODO: Describe what this renders.\n\n  @param {array} items - TODO\n  @param {number} scale - TODO\n{% enddoc %}\n"
+/// );
+/// ```
+pub fn infer_doc_stub(source: &str) -> Option<DocStub> {
+    if parse_liquid_file(source).doc().is_some() {
+        return None;
+    }
+
+    let mut reads: Vec<Read> = Vec::new();
+    let mut skipped: Vec<String> = Vec::new();
+    for reference in free_variables(source) {
+        if !is_param_name(&reference.name) {
+            if !skipped.contains(&reference.name) {
+                skipped.push(reference.name);
+            }
+            continue;
+        }
+        let index = match reads.iter().position(|read| read.name == reference.name) {
+            Some(index) => index,
+            None => {
+                reads.push(Read {
+                    name: reference.name,
+                    hints: Vec::new(),
+                    only_conditions: true,
+                });
+                reads.len() - 1
+            }
+        };
+        let read = &mut reads[index];
+        read.only_conditions &= reference.hints == [UsageHint::Condition];
+        for hint in reference.hints {
+            if !read.hints.contains(&hint) {
+                read.hints.push(hint);
+            }
+        }
+    }
+
+    Some(DocStub {
+        params: reads.into_iter().map(Read::into_param).collect(),
+        skipped,
+    })
+}
+
+impl DocStub {
+    /// The stub as a [`DocBuilder`], with TODO descriptions to fill in.
+    pub fn builder(&self) -> DocBuilder {
+        self.params.iter().fold(
+            DocBuilder::new().description(DESCRIPTION_TODO),
+            |builder, stub| {
+                let mut param = Param::new(&stub.name)
+                    .ty(&stub.param_type.to_string())
+                    .description(PARAM_TODO);
+                if !stub.required {
+                    param = param.optional();
+                }
+                builder.param(param)
+            },
+        )
+    }
+
+    /// The `{% doc %}` block, ready to insert at the top of the template.
+    pub fn to_text(&self) -> Result<String, DocBuilderError> {
+        self.builder().to_doc_block()
+    }
+
+    /// Inserts the `{% doc %}` block at the start of the template.
+    pub fn text_edit(&self) -> Result<TextEdit, DocBuilderError> {
+        Ok(TextEdit {
+            position: Position::new(0, 0, None),
+            new_text: self.to_text()?,
+        })
+    }
+}
+
+/// Everything the reads of one variable hint at.
+struct Read {
+    name: String,
+    hints: Vec<UsageHint>,
+    only_conditions: bool,
+}
+
+impl Read {
+    fn into_param(self) -> StubParam {
+        let has = |hint| self.hints.contains(&hint);
+        let param_type = if has(UsageHint::Iterated) || has(UsageHint::Size) {
+            ParamType::Array(None)
+        } else if has(UsageHint::Arithmetic) {
+            ParamType::Number
+        } else if has(UsageHint::Property) {
+            ParamType::Object
+        } else if self.only_conditions {
+            ParamType::Boolean
+        } else {
+            ParamType::String
+        };
+
+        StubParam {
+            required: !has(UsageHint::Default) && !has(UsageHint::Condition),
+            param_type,
+            name: self.name,
+            hints: self.hints,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::liquid_doc::LiquidDoc;
+    use pretty_assertions::assert_eq;
+
+    fn params(source: &str) -> Vec<(String, String, bool)> {
+        infer_doc_stub(source)
+            .unwrap()
+            .params
+            .into_iter()
+            .map(|param| (param.name, param.param_type.to_string(), param.required))
+            .collect()
+    }
+
+    #[test]
+    fn infers_types_from_usage() {
+        let source = concat!(
+            "{% if show_vendor %}{{ product.vendor }}{% endif %}",
+            "{% assign width = columns | times: 100 | divided_by: total %}",
+            "{% if photos.size > 0 %}{{ heading | default: 'Images' }}{% endif %}",
+            "{% for tag in tags %}{{ tag }}{% endfor %}",
+            "{% if title %}<h2>{{ title }}</h2>{% endif %}",
+            "{{ label }}",
+        );
+
+        assert_eq!(
+            params(source),
+            vec![
+                ("show_vendor".to_string(), "boolean".to_string(), false),
+                ("product".to_string(), "object".to_string(), true),
+                ("columns".to_string(), "number".to_string(), true),
+                ("total".to_string(), "number".to_string(), true),
+                ("photos".to_string(), "array".to_string(), true),
+                ("heading".to_string(), "string".to_string(), false),
+                ("tags".to_string(), "array".to_string(), true),
+                ("title".to_string(), "string".to_string(), false),
+                ("label".to_string(), "string".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn the_edit_documents_every_free_variable() {
+        let source = "<h2>{{ title | default: product.title }}</h2>\n";
+        let edit = infer_doc_stub(source).unwrap().text_edit().unwrap();
+
+        let mut fixed = source.to_string();
+        fixed.replace_range(edit.position.start..edit.position.end, &edit.new_text);
+        assert!(fixed.starts_with("{% doc %}\n  TODO: Describe what this renders.\n\n  @param {string} [title] - TODO\n  @param {object} product - TODO\n{% enddoc %}\n<h2>"));
+
+        let file = parse_liquid_file(&fixed);
+        let doc = LiquidDoc::from_ast(&file.doc().unwrap().ast);
+        assert_eq!(doc.params.len(), 2);
+        assert_eq!(crate::check_param_usage(&fixed), vec![]);
+    }
+
+    #[test]
+    fn names_a_param_cannot_have_are_skipped() {
+        let stub = infer_doc_stub("{% if ok? %}{{ sold-out }}{{ ok? }}{% endif %}").unwrap();
+
+        assert_eq!(stub.params.len(), 1);
+        assert_eq!(stub.params[0].name, "sold-out");
+        assert_eq!(stub.skipped, vec!["ok?".to_string()]);
+        let text = stub.to_text().unwrap();
+        let file = parse_liquid_file(&text);
+        let doc = LiquidDoc::from_ast(&file.doc().unwrap().ast);
+        assert!(doc.param("sold-out").is_some());
+        assert!(doc.unknown_tags.is_empty());
+    }
+
+    #[test]
+    fn documented_templates_have_no_stub() {
+        assert_eq!(infer_doc_stub("{% doc %}{% enddoc %}{{ title }}"), None);
+    }
+}
@@ -0,0 +1,484 @@
+use std::collections::VecDeque;
+use std::ops::Range;
+
+use pest::iterators::{Pair, Pairs};
+use pest::Parser;
+
+use crate::ast::{
+    parse_fence, split_title, unescape, LiquidDocDescriptionNode, LiquidDocExampleNode,
+    LiquidDocParamNode, LiquidDocUnknownTagNode, LiquidNode, Position, TextNode,
+};
+use crate::parser::{LiquidParser, ParseOptions, Rule};
+
+/// A step of a pull parse. Spans are positions in the input, shifted by the position offset;
+/// slice the input with them to get the text.
+///
+/// Every node is either a single `Text`/`Prompt` event or opens with a `Start*` event and closes
+/// with the matching `End*` event, with the spans of its parts in between in source order.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DocEvent {
+    StartDescription {
+        position: Position,
+        is_implicit: bool,
+        is_inline: bool,
+    },
+    EndDescription,
+    StartParam {
+        position: Position,
+        required: bool,
+    },
+    /// The type between the braces.
+    ParamType(Position),
+    /// The name, between the brackets when it is optional.
+    ParamName(Position),
+    ParamDescription(Position),
+    EndParam,
+    StartExample {
+        position: Position,
+        is_inline: bool,
+    },
+    /// A part of the example's content, after `Content`.
+    ExampleTitle(Position),
+    ExampleLanguage(Position),
+    ExampleCode(Position),
+    EndExample,
+    StartUnknownTag {
+        position: Position,
+    },
+    /// The unknown tag's name, without the `@`.
+    TagName(Position),
+    EndUnknownTag,
+    /// The body of the open description, example or unknown tag.
+    Content(Position),
+    /// A `@prompt` and its content.
+    Prompt(Position),
+    /// Text outside of any tag.
+    Text(Position),
+}
+
+/// Pulls [`DocEvent`]s from LiquidDoc content, one node's worth at a time.
+///
+/// ```
+/// use liquid_doc_parser::{parse_doc_events, DocEvent, ParseOptions};
+///
+/// let input = "@param {string} title";
+/// let options = ParseOptions::new();
+/// let names: Vec<&str> = parse_doc_events(input, &options)
+///     .unwrap()
+///     .filter_map(|event| match event {
+///         DocEvent::ParamName(span) => Some(&input[span.start..span.end]),
+///         _ => None,
+///     })
+///     .collect();
+/// assert_eq!(names, vec!["title"]);
+/// ```
+pub struct DocEvents<'a> {
+    pairs: Pairs<'a, Rule>,
+    options: &'a ParseOptions,
+    pending: VecDeque<DocEvent>,
+}
+
+impl Iterator for DocEvents<'_> {
+    type Item = DocEvent;
+
+    fn next(&mut self) -> Option<DocEvent> {
+        while self.pending.is_empty() {
+            let pair = self.pairs.next()?;
+            let pair = match pair.as_rule() {
+                Rule::LiquidDocNode => pair
+                    .into_inner()
+                    .next()
+                    .expect("Expected a LiquidDocNode to wrap a node"),
+                _ => pair,
+            };
+            pair_events(pair, self.options, &mut self.pending);
+        }
+        self.pending.pop_front()
+    }
+}
+
+/// Parses `input` into a pull parser. Returns `None` when `input` does not parse.
+pub fn parse_doc_events<'a>(input: &'a str, options: &'a ParseOptions) -> Option<DocEvents<'a>> {
+    let mut pairs = LiquidParser::parse(Rule::Document, input).ok()?;
+    let document = pairs.next().expect("Expected a Document");
+
+    Some(DocEvents {
+        pairs: document.into_inner(),
+        options,
+        pending: VecDeque::new(),
+    })
+}
+
+/// Builds the node for a pair below `Document` and `LiquidDocNode`, or `None` for pairs that
+/// produce no node, such as an empty implicit description.
+pub(crate) fn node_from_pair(pair: Pair<Rule>, options: &ParseOptions) -> Option<LiquidNode> {
+    let builder = TreeBuilder::new(pair.get_input(), options.position_offset);
+    let mut events = VecDeque::new();
+    pair_events(pair, options, &mut events);
+    builder.next_node(&mut events.into_iter())
+}
+
+fn pair_events(pair: Pair<Rule>, options: &ParseOptions, events: &mut VecDeque<DocEvent>) {
+    let position = Position::from_pair(&pair, options.position_offset);
+    let text = pair.as_str();
+    match pair.as_rule() {
+        Rule::ImplicitDescription if text.is_empty() => {}
+        // If the description starts with '@', liquid-html-parser treats it as a text node
+        Rule::ImplicitDescription if options.fallback_text_nodes && text.starts_with('@') => {
+            events.push_back(DocEvent::Text(position))
+        }
+        Rule::ImplicitDescription => events.extend([
+            DocEvent::StartDescription {
+                position,
+                is_implicit: true,
+                is_inline: true,
+            },
+            DocEvent::Content(position),
+            DocEvent::EndDescription,
+        ]),
+        Rule::DescriptionNode => {
+            let (content_start, is_inline) = tag_content(text, "@description");
+            events.extend([
+                DocEvent::StartDescription {
+                    position,
+                    is_implicit: false,
+                    is_inline,
+                },
+                DocEvent::Content(sub_span(position, content_start..text.len())),
+                DocEvent::EndDescription,
+            ]);
+        }
+        Rule::ParamNode => {
+            let mut inner = pair.into_inner().peekable();
+            let param_type = inner
+                .next_if(|pair| pair.as_rule() == Rule::paramType)
+                .map(|pair| bracket_content(&pair, options.position_offset));
+            let name = inner.next().expect("Expected a paramName");
+            let required = !name.as_str().starts_with('[') && !name.as_str().ends_with(']');
+            let description = inner
+                .next()
+                .filter(|pair| !pair.as_str().is_empty())
+                .map(|pair| Position::from_pair(&pair, options.position_offset));
+
+            events.push_back(DocEvent::StartParam { position, required });
+            events.extend(param_type.map(DocEvent::ParamType));
+            events.push_back(DocEvent::ParamName(bracket_content(
+                &name,
+                options.position_offset,
+            )));
+            events.extend(description.map(DocEvent::ParamDescription));
+            events.push_back(DocEvent::EndParam);
+        }
+        Rule::ExampleNode => {
+            let (content_start, is_inline) = tag_content(text, "@example");
+            let content = &text[content_start..];
+            let content_span = sub_span(position, content_start..text.len());
+            let (title, body_start) = split_title(content, is_inline);
+            let fence = parse_fence(content, body_start);
+
+            events.extend([
+                DocEvent::StartExample {
+                    position,
+                    is_inline,
+                },
+                DocEvent::Content(content_span),
+            ]);
+            events.extend(title.map(|range| DocEvent::ExampleTitle(sub_span(content_span, range))));
+            if let Some((language, code)) = fence {
+                events.extend(
+                    language.map(|range| DocEvent::ExampleLanguage(sub_span(content_span, range))),
+                );
+                events.push_back(DocEvent::ExampleCode(sub_span(content_span, code)));
+            }
+            events.push_back(DocEvent::EndExample);
+        }
+        Rule::FallbackNode if !options.fallback_text_nodes => {
+            let mut inner = pair.into_inner();
+            let tag_name = inner.next().expect("Expected an unknownTagName");
+            let content = inner.next().expect("Expected an unknownTagContent");
+            events.extend([
+                DocEvent::StartUnknownTag { position },
+                DocEvent::TagName(Position::from_pair(&tag_name, options.position_offset)),
+                DocEvent::Content(Position::from_pair(&content, options.position_offset)),
+                DocEvent::EndUnknownTag,
+            ]);
+        }
+        Rule::PromptNode => events.push_back(DocEvent::Prompt(position)),
+        Rule::FallbackNode | Rule::TextNode if !text.is_empty() => {
+            events.push_back(DocEvent::Text(position))
+        }
+        // Empty text, and rules that never produce a node
+        _ => {}
+    }
+}
+
+/// Where the content after `tag` starts, and whether it stays on the tag's line.
+/// A line break right after the tag is skipped, otherwise a single space is.
+fn tag_content(text: &str, tag: &str) -> (usize, bool) {
+    let after_tag = &text[tag.len()..];
+    if let Some(line_break) = crate::ast::line_break_len(after_tag) {
+        return (tag.len() + line_break, false);
+    }
+    if after_tag.starts_with(' ') {
+        return (tag.len() + 1, true);
+    }
+    (0, true)
+}
+
+/// The span inside one pair of outer brackets, e.g. `string` in `{string}` or `name` in `[name]`.
+fn bracket_content(pair: &Pair<Rule>, position_offset: Option<usize>) -> Position {
+    let position = Position::from_pair(pair, position_offset);
+    let text = pair.as_str();
+    let is_bracket = |c| c == '{' || c == '}' || c == '[' || c == ']';
+    // Only the outer pair is stripped, so an escaped `\}` right before the closing bracket survives
+    if text.len() < 2 || !text.starts_with(is_bracket) || !text.ends_with(is_bracket) {
+        return position;
+    }
+    sub_span(position, 1..text.len() - 1)
+}
+
+fn sub_span(position: Position, range: Range<usize>) -> Position {
+    Position::new(
+        position.start + range.start,
+        position.start + range.end,
+        None,
+    )
+}
+
+/// Builds nodes by consuming [`DocEvent`]s, slicing each span's text out of the input.
+pub(crate) struct TreeBuilder<'a> {
+    input: &'a str,
+    offset: usize,
+}
+
+impl<'a> TreeBuilder<'a> {
+    pub(crate) fn new(input: &'a str, position_offset: Option<usize>) -> Self {
+        TreeBuilder {
+            input,
+            offset: position_offset.unwrap_or(0),
+        }
+    }
+
+    /// Consumes the events of the next node and builds it.
+    pub(crate) fn next_node(
+        &self,
+        events: &mut impl Iterator<Item = DocEvent>,
+    ) -> Option<LiquidNode> {
+        let node = match events.next()? {
+            DocEvent::StartDescription {
+                position,
+                is_implicit,
+                is_inline,
+            } => {
+                let mut content = None;
+                for event in events.by_ref() {
+                    match event {
+                        DocEvent::Content(span) => content = Some(self.text(span, true)),
+                        DocEvent::EndDescription => break,
+                        other => panic!("Unexpected {:?} in a description", other),
+                    }
+                }
+                LiquidNode::LiquidDocDescriptionNode(LiquidDocDescriptionNode::new(
+                    content.expect("Expected a description to have content"),
+                    is_implicit,
+                    is_inline,
+                    position,
+                    self.source(position).to_string(),
+                ))
+            }
+            DocEvent::StartParam { position, required } => {
+                let (mut param_type, mut name, mut description) = (None, None, None);
+                for event in events.by_ref() {
+                    match event {
+                        DocEvent::ParamType(span) => param_type = Some(self.text(span, true)),
+                        // Names are identifiers, so they have nothing to unescape
+                        DocEvent::ParamName(span) => name = Some(self.text(span, false)),
+                        DocEvent::ParamDescription(span) => {
+                            description = Some(self.text(span, true))
+                        }
+                        DocEvent::EndParam => break,
+                        other => panic!("Unexpected {:?} in a param", other),
+                    }
+                }
+                LiquidNode::LiquidDocParamNode(LiquidDocParamNode::new(
+                    position,
+                    self.source(position).to_string(),
+                    param_type,
+                    name.expect("Expected a param to have a name"),
+                    description,
+                    required,
+                ))
+            }
+            DocEvent::StartExample {
+                position,
+                is_inline,
+            } => {
+                let (mut content, mut title, mut language, mut code) = (None, None, None, None);
+                for event in events.by_ref() {
+                    match event {
+                        DocEvent::Content(span) => content = Some(self.text(span, true)),
+                        DocEvent::ExampleTitle(span) => title = Some(self.text(span, true)),
+                        // Fenced code is kept verbatim, like Markdown does
+                        DocEvent::ExampleLanguage(span) => language = Some(self.text(span, false)),
+                        DocEvent::ExampleCode(span) => code = Some(self.text(span, false)),
+                        DocEvent::EndExample => break,
+                        other => panic!("Unexpected {:?} in an example", other),
+                    }
+                }
+                let mut node = LiquidDocExampleNode::new(
+                    content.expect("Expected an example to have content"),
+                    is_inline,
+                    position,
+                    self.source(position).to_string(),
+                );
+                node.title = title.map(|t| Box::new(LiquidNode::TextNode(t)));
+                node.language = language.map(|l| Box::new(LiquidNode::TextNode(l)));
+                node.code = code.map(|c| Box::new(LiquidNode::TextNode(c)));
+                LiquidNode::LiquidDocExampleNode(node)
+            }
+            DocEvent::StartUnknownTag { position } => {
+                let (mut tag_name, mut content) = (None, None);
+                for event in events.by_ref() {
+                    match event {
+                        DocEvent::TagName(span) => tag_name = Some(self.text(span, false)),
+                        DocEvent::Content(span) => content = Some(self.text(span, true)),
+                        DocEvent::EndUnknownTag => break,
+                        other => panic!("Unexpected {:?} in an unknown tag", other),
+                    }
+                }
+                LiquidNode::LiquidDocUnknownTagNode(LiquidDocUnknownTagNode::new(
+                    tag_name.expect("Expected an unknown tag to have a name"),
+                    content.expect("Expected an unknown tag to have content"),
+                    position,
+                    self.source(position).to_string(),
+                ))
+            }
+            // Prompts are kept as text nodes for liquid-html-parser parity
+            DocEvent::Prompt(span) | DocEvent::Text(span) => {
+                LiquidNode::TextNode(self.text(span, true))
+            }
+            other => panic!("Unexpected {:?} outside of a node", other),
+        };
+        Some(node)
+    }
+
+    fn source(&self, position: Position) -> &'a str {
+        &self.input[position.start - self.offset..position.end - self.offset]
+    }
+
+    fn text(&self, position: Position, unescaped: bool) -> TextNode {
+        let source = self.source(position);
+        let value = if unescaped {
+            unescape(source)
+        } else {
+            source.to_string()
+        };
+        TextNode::new(value, position, source.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn events(input: &str) -> Vec<DocEvent> {
+        parse_doc_events(input, &ParseOptions::new())
+            .unwrap()
+            .collect()
+    }
+
+    fn span(start: usize, end: usize) -> Position {
+        Position::new(start, end, None)
+    }
+
+    #[test]
+    fn param_events() {
+        assert_eq!(
+            events("@param {string} [title] - The title"),
+            vec![
+                DocEvent::StartParam {
+                    position: span(0, 35),
+                    required: false
+                },
+                DocEvent::ParamType(span(8, 14)),
+                DocEvent::ParamName(span(17, 22)),
+                DocEvent::ParamDescription(span(26, 35)),
+                DocEvent::EndParam,
+            ]
+        );
+    }
+
+    #[test]
+    fn example_events_include_its_parts() {
+        let input = "@example Card\n```liquid\n{% render 'card' %}\n```";
+        let spans: Vec<(String, &str)> = events(input)
+            .into_iter()
+            .filter_map(|event| {
+                let position = match event {
+                    DocEvent::Content(position)
+                    | DocEvent::ExampleTitle(position)
+                    | DocEvent::ExampleLanguage(position)
+                    | DocEvent::ExampleCode(position) => position,
+                    _ => return None,
+                };
+                let name = format!("{:?}", event);
+                let name = name.split('(').next().unwrap().to_string();
+                Some((name, &input[position.start..position.end]))
+            })
+            .collect();
+
+        assert_eq!(
+            spans,
+            vec![
+                ("Content".to_string(), &input[9..]),
+                ("ExampleTitle".to_string(), "Card"),
+                ("ExampleLanguage".to_string(), "liquid"),
+                ("ExampleCode".to_string(), "{% render 'card' %}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn text_prompts_and_unknown_tags() {
+        let input = "Renders a card\n@deprecated use card-v2\n@prompt Write a card";
+        let events = events(input);
+
+        assert_eq!(events.len(), 8);
+        assert_eq!(
+            events[3..7],
+            [
+                DocEvent::StartUnknownTag {
+                    position: span(15, 38)
+                },
+                DocEvent::TagName(span(16, 26)),
+                DocEvent::Content(span(27, 38)),
+                DocEvent::EndUnknownTag,
+            ]
+        );
+        assert_eq!(events[7], DocEvent::Prompt(span(39, 59)));
+    }
+
+    #[test]
+    fn events_are_shifted_by_the_offset() {
+        let options = ParseOptions::new().with_position_offset(Some(9));
+        let events: Vec<_> = parse_doc_events("Hello", &options).unwrap().collect();
+
+        assert_eq!(events[1], DocEvent::Content(span(9, 14)));
+    }
+
+    #[test]
+    fn tree_builder_consumes_the_events() {
+        let input = include_str!("../../web/fixtures/fixture1.liquid");
+        let options = ParseOptions::new().with_position_offset(Some(10));
+        let mut events = parse_doc_events(input, &options).unwrap();
+        let builder = TreeBuilder::new(input, options.position_offset);
+
+        let nodes: Vec<LiquidNode> =
+            std::iter::from_fn(|| builder.next_node(&mut events)).collect();
+        assert_eq!(
+            nodes,
+            crate::parse_liquid_string(input, Some(10)).unwrap().nodes
+        );
+    }
+}
@@ -0,0 +1,537 @@
+use serde::Serialize;
+
+use crate::ast::{LiquidAST, Position};
+use crate::builder::{is_param_type, DocBuilder, Example, Param};
+use crate::diagnostics::{Diagnostic, TextEdit};
+use crate::liquid_doc::ParamType;
+use crate::liquid_file::{parse_liquid_file, parse_liquid_file_with_options};
+use crate::liquid_tags::{closing_tag, next_tag};
+use crate::parser::ParseOptions;
+
+/// Headings of the list of params, compared without case or a trailing `:`.
+const PARAMS_HEADINGS: [&str; 5] = ["accepts", "parameters", "params", "arguments", "args"];
+
+/// Headings of the render calls showing how to use the snippet.
+const USAGE_HEADINGS: [&str; 3] = ["usage", "example", "examples"];
+
+/// A leading `{% comment %}` documenting a snippet the way Dawn does, converted to LiquidDoc.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct LegacyDocMigration {
+    /// From the `{%` of the `{% comment %}` to the `%}` of its `{% endcomment %}`.
+    #[serde(rename = "commentPosition")]
+    pub comment_position: Position,
+    /// The doc, positioned as in the template once the edit is applied.
+    pub ast: LiquidAST,
+    /// Replaces the comment with the `{% doc %}` block.
+    pub edit: TextEdit,
+    /// The lines that could not be read, which are kept in the description, and the types that
+    /// could not be kept.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Converts the comment at the top of a snippet written in Dawn's convention:
+///
+/// ```text
+/// {% comment %}
+///   Renders a product card
+///
+///   Accepts:
+///   - product: {Object} Product Liquid object (optional)
+///
+///   Usage:
+///   {% render 'card-product', product: product %}
+/// {% endcomment %}
+/// ```
+///
+/// The text before the params becomes the description and the `Usage:` section an
+/// `@example`. Params may start with `-` or `*`, follow their name with `:` or a `{Type}`,
+/// and mark themselves `(optional)` anywhere in their description. More indented lines
+/// continue the previous param. Other lines of the list are reported as
+/// `UnparseableLegacyDoc` and appended to the description, so nothing is lost.
+///
+/// `nil` in a union type such as `{Object | nil}` makes the param optional. Other unions cannot
+/// be LiquidDoc types, so their params are left untyped and reported as
+/// `UnrepresentableLegacyType`.
+///
+/// Comments before it without params or usage, such as `theme-check-disable`, are skipped.
+/// Returns `None` when there is no such comment, or when the template has a `{% doc %}` block,
+/// and an `UnmigratableLegacyDoc` error when the comment cannot be written as a `{% doc %}`
+/// block, e.g. when its usage is already in a Markdown fence.
+///
+/// ```
+/// use liquid_doc_parser::{migrate_legacy_doc, LiquidDoc};
+///
+/// let source = "{% comment %}\n  Renders a price\n\n  Accepts:\n  - product: {Object} Product Liquid object (optional)\n{% endcomment %}\n{{ product.price }}";
+/// let migration = migrate_legacy_doc(source).unwrap().unwrap();
+/// assert_eq!(
+///     migration.edit.new_text,
+///     "{% doc %}\n  Renders a price\n\n  @param {object} [product] - Product Liquid object\n{% enddoc %}"
+/// );
+/// assert!(!LiquidDoc::from_ast(&migration.ast).param("product").unwrap().required);
+/// ```
+pub fn migrate_legacy_doc(source: &str) -> Option<Result<LegacyDocMigration, Diagnostic>> {
+    if parse_liquid_file(source).doc().is_some() {
+        return None;
+    }
+
+    let mut cursor = 0;
+    loop {
+        let start = cursor + (source[cursor..].len() - source[cursor..].trim_start().len());
+        let open = next_tag(source, start, false).filter(|tag| tag.start == start)?;
+        if open.name != "comment" {
+            return None;
+        }
+        let close = closing_tag(source, open.end, "comment", "endcomment", true)?;
+        let legacy = LegacyDoc::read(&source[open.end..close.start], open.end);
+        if legacy.is_doc() {
+            let position = Position::new(open.start, close.end, None);
+            return Some(legacy.into_migration(position).map_err(|reason| {
+                Diagnostic::error(
+                    "UnmigratableLegacyDoc",
+                    format!(
+                        "The comment cannot be migrated to a {{% doc %}} block: {}",
+                        reason
+                    ),
+                    position,
+                )
+            }));
+        }
+        cursor = close.end;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    Description,
+    Params,
+    Usage,
+}
+
+/// A `@param` read from a line of the list.
+struct LegacyParam {
+    name: String,
+    /// The type between the braces, as written.
+    raw_type: Option<String>,
+    param_type: Option<String>,
+    /// Whether the type allows `nil`, e.g. `{Object | nil}`.
+    nullable: bool,
+    /// Everything after the type, including continuation lines and any `(optional)` marker.
+    description: String,
+    /// The indentation of the bullet, which continuation lines go past.
+    indent: usize,
+}
+
+/// The sections of a comment, read line by line.
+#[derive(Default)]
+struct LegacyDoc<'a> {
+    description: Vec<&'a str>,
+    params: Vec<LegacyParam>,
+    usage: Vec<&'a str>,
+    has_params_heading: bool,
+    /// The lines of the list that are not params, with their diagnostics.
+    unparsed: Vec<&'a str>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> LegacyDoc<'a> {
+    fn read(body: &'a str, body_start: usize) -> Self {
+        let mut doc = LegacyDoc::default();
+        let mut section = Section::Description;
+        let mut line_start = body_start;
+
+        for line in body.split_inclusive('\n') {
+            let start = line_start;
+            line_start += line.len();
+            let line = line.trim_end();
+            let trimmed = line.trim_start();
+            let indent = line.len() - trimmed.len();
+
+            let heading = trimmed.trim_end_matches(':').trim().to_lowercase();
+            if PARAMS_HEADINGS.contains(&heading.as_str()) {
+                section = Section::Params;
+                doc.has_params_heading = true;
+                continue;
+            }
+            if USAGE_HEADINGS.contains(&heading.as_str()) {
+                section = Section::Usage;
+                continue;
+            }
+
+            match section {
+                Section::Description => doc.description.push(trimmed),
+                Section::Usage => doc.usage.push(line),
+                Section::Params if trimmed.is_empty() => {}
+                Section::Params => {
+                    let position = Position::new(start + indent, start + line.len(), None);
+                    if let Some(mut param) = parse_param(trimmed, indent) {
+                        if let Some(raw_type) = &param.raw_type {
+                            match normalize_type(raw_type) {
+                                Some((param_type, nullable)) => {
+                                    param.param_type = param_type;
+                                    param.nullable = nullable;
+                                }
+                                None => doc.diagnostics.push(Diagnostic::warning(
+                                    "UnrepresentableLegacyType",
+                                    format!(
+                                        "'{{{}}}' is not a LiquidDoc type, so '{}' is left untyped",
+                                        raw_type, param.name
+                                    ),
+                                    position,
+                                )),
+                            }
+                        }
+                        doc.params.push(param);
+                        continue;
+                    }
+                    match doc.params.last_mut() {
+                        Some(previous) if indent > previous.indent && !is_bullet(trimmed) => {
+                            previous.description.push(' ');
+                            previous.description.push_str(trimmed);
+                        }
+                        _ => {
+                            doc.unparsed.push(trimmed);
+                            doc.diagnostics.push(Diagnostic::warning(
+                                "UnparseableLegacyDoc",
+                                format!(
+                                    "'{}' is not a param, so it is kept in the description",
+                                    trimmed
+                                ),
+                                position,
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        doc
+    }
+
+    /// Whether the comment documents the snippet, rather than being a note or a directive.
+    fn is_doc(&self) -> bool {
+        self.has_params_heading || self.usage.iter().any(|line| !line.trim().is_empty())
+    }
+
+    /// Builds the `{% doc %}` block replacing the comment at `comment_position`, or says why it
+    /// cannot.
+    fn into_migration(self, comment_position: Position) -> Result<LegacyDocMigration, String> {
+        let mut lines = self.description;
+        lines.extend(&self.unparsed);
+        let description = lines.join("\n").trim().to_string();
+
+        let mut builder = DocBuilder::new();
+        if !description.is_empty() {
+            builder = builder.description(&description);
+        }
+        for legacy in &self.params {
+            let mut description = legacy.description.clone();
+            let optional = strip_optional(&mut description) || legacy.nullable;
+            let mut param = Param::new(&legacy.name);
+            if let Some(param_type) = &legacy.param_type {
+                param = param.ty(param_type);
+            }
+            if optional {
+                param = param.optional();
+            }
+            if !description.is_empty() {
+                param = param.description(&description);
+            }
+            builder = builder.param(param);
+        }
+        let usage = dedent(&self.usage);
+        if !usage.is_empty() {
+            builder = builder.example(Example::new(&usage));
+        }
+
+        let block = builder.to_doc_block().map_err(|error| error.to_string())?;
+        let block = block.trim_end_matches('\n');
+        let file = parse_liquid_file_with_options(
+            block,
+            &ParseOptions::new().with_position_offset(Some(comment_position.start)),
+        );
+        // A `{% enddoc %}` in the comment's text would close the block early
+        let ast = match file.doc_blocks.as_slice() {
+            [doc] if doc.position.end - doc.position.start == block.len() => doc.ast.clone(),
+            _ => return Err("its text closes the block early".to_string()),
+        };
+
+        Ok(LegacyDocMigration {
+            comment_position,
+            ast,
+            edit: TextEdit {
+                position: comment_position,
+                new_text: block.to_string(),
+            },
+            diagnostics: self.diagnostics,
+        })
+    }
+}
+
+fn is_bullet(line: &str) -> bool {
+    line.starts_with('-') || line.starts_with('*')
+}
+
+/// Reads `- name: {Type} Description (optional)`, where the `:` may be left out before a type.
+/// The type is kept as written, for [`normalize_type`].
+fn parse_param(line: &str, indent: usize) -> Option<LegacyParam> {
+    let rest = line
+        .strip_prefix('-')
+        .or_else(|| line.strip_prefix('*'))?
+        .trim_start();
+    let name_len = rest
+        .find(|c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '-')
+        .unwrap_or(rest.len());
+    let (name, rest) = rest.split_at(name_len);
+    let rest = rest.trim_start();
+    if name.is_empty() || !(rest.starts_with(':') || rest.starts_with('{')) {
+        return None;
+    }
+
+    let mut rest = rest.strip_prefix(':').unwrap_or(rest).trim_start();
+    let mut raw_type = None;
+    if let Some(type_and_rest) = rest.strip_prefix('{') {
+        let close = type_and_rest.find('}')?;
+        raw_type = Some(type_and_rest[..close].trim().to_string());
+        rest = type_and_rest[close + 1..].trim_start_matches(':');
+    }
+
+    Some(LegacyParam {
+        name: name.to_string(),
+        raw_type,
+        param_type: None,
+        nullable: false,
+        description: rest.trim().to_string(),
+        indent,
+    })
+}
+
+/// The LiquidDoc type of a legacy type, and whether it allows `nil`. `nil` and `null` in a
+/// union such as `Object | nil` make the param optional rather than being part of its type.
+/// Returns `None` for types a `@param` cannot hold, such as `String | Number`.
+fn normalize_type(raw_type: &str) -> Option<(Option<String>, bool)> {
+    let mut nullable = false;
+    let mut types = Vec::new();
+    for part in raw_type.split('|').map(str::trim) {
+        match part.to_lowercase().as_str() {
+            "nil" | "null" => nullable = true,
+            "" => {}
+            part => types.push(ParamType::parse(part).to_string()),
+        }
+    }
+    match types.as_slice() {
+        [] if nullable => Some((None, true)),
+        [param_type] if is_param_type(param_type) => Some((Some(param_type.clone()), nullable)),
+        _ => None,
+    }
+}
+
+/// Removes an `(optional)` or `[optional]` marker from a description, returning whether there
+/// was one.
+fn strip_optional(description: &mut String) -> bool {
+    let lowercase = description.to_lowercase();
+    let marker = ["(optional)", "[optional]"]
+        .iter()
+        .find_map(|marker| lowercase.find(marker).map(|at| at..at + marker.len()));
+    if let Some(marker) = &marker {
+        description.replace_range(marker.clone(), "");
+        *description = description.split_whitespace().collect::<Vec<_>>().join(" ");
+    }
+    marker.is_some()
+}
+
+/// Joins the usage lines, without the surrounding blank lines or their common indentation.
+fn dedent(lines: &[&str]) -> String {
+    let first = lines.iter().position(|line| !line.trim().is_empty());
+    let last = lines.iter().rposition(|line| !line.trim().is_empty());
+    let (Some(first), Some(last)) = (first, last) else {
+        return String::new();
+    };
+    let lines = &lines[first..=last];
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or(""))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::liquid_doc::LiquidDoc;
+    use pretty_assertions::assert_eq;
+
+    const PRICE: &str = r#"{% comment %}
+    Renders a list of product's price (regular, sale)
+
+    Accepts:
+    - product: {Object} Product Liquid object (optional)
+    - use_variant: {Boolean} Renders selected or first variant price instead of overall product pricing (optional)
+    - price_class: {String} Adds a price class to the price element (optional)
+
+    Usage:
+    {% render 'price', product: product %}
+{% endcomment %}
+<div class="price">{{ product.price | money }}</div>
+"#;
+
+    fn apply(source: &str, edit: &TextEdit) -> String {
+        let mut migrated = source.to_string();
+        migrated.replace_range(edit.position.start..edit.position.end, &edit.new_text);
+        migrated
+    }
+
+    #[test]
+    fn migrates_dawn_comments() {
+        let migration = migrate_legacy_doc(PRICE).unwrap().unwrap();
+        let migrated = apply(PRICE, &migration.edit);
+
+        assert_eq!(
+            migrated,
+            r#"{% doc %}
+  Renders a list of product's price (regular, sale)
+
+  @param {object} [product] - Product Liquid object
+  @param {boolean} [use_variant] - Renders selected or first variant price instead of overall product pricing
+  @param {string} [price_class] - Adds a price class to the price element
+
+  @example
+  {% render 'price', product: product %}
+{% enddoc %}
+<div class="price">{{ product.price | money }}</div>
+"#
+        );
+        assert_eq!(migration.diagnostics, vec![]);
+        assert_eq!(
+            parse_liquid_file(&migrated).doc().unwrap().ast,
+            migration.ast
+        );
+
+        let doc = LiquidDoc::from_ast(&migration.ast);
+        assert_eq!(
+            doc.description_text(),
+            Some("Renders a list of product's price (regular, sale)")
+        );
+        assert_eq!(doc.params.len(), 3);
+        assert_eq!(
+            doc.examples[0].code_or_content().trim(),
+            "{% render 'price', product: product %}"
+        );
+    }
+
+    #[test]
+    fn reads_common_variants() {
+        let source = concat!(
+            "{%- comment -%} theme-check-disable {%- endcomment -%}\n",
+            "{%- comment -%}\n",
+            "  Parameters\n",
+            "  * block {Object} The block\n",
+            "  - image_width: {Number}: Width of the image,\n",
+            "      in pixels [Optional]\n",
+            "  - label: The label\n",
+            "{%- endcomment -%}",
+        );
+        let migration = migrate_legacy_doc(source).unwrap().unwrap();
+        let doc = LiquidDoc::from_ast(&migration.ast);
+
+        assert_eq!(
+            &source[migration.comment_position.start..migration.comment_position.end],
+            "{%- comment -%}\n  Parameters\n  * block {Object} The block\n  - image_width: {Number}: Width of the image,\n      in pixels [Optional]\n  - label: The label\n{%- endcomment -%}"
+        );
+        assert!(migration
+            .edit
+            .new_text
+            .starts_with("{% doc %}\n  @param {object} block - The block\n"));
+        let width = doc.param("image_width").unwrap();
+        assert_eq!(width.param_type, Some(ParamType::Number));
+        assert!(!width.required);
+        assert_eq!(
+            width.description.as_deref(),
+            Some("Width of the image, in pixels")
+        );
+        assert_eq!(
+            doc.param("block").unwrap().param_type,
+            Some(ParamType::Object)
+        );
+        assert_eq!(doc.param("label").unwrap().param_type, None);
+    }
+
+    #[test]
+    fn reports_and_keeps_unparseable_lines() {
+        let source = "{% comment %}\n  Renders a badge\n  Accepts:\n  - text: {String} The text\n  Anything else is ignored\n{% endcomment %}";
+        let migration = migrate_legacy_doc(source).unwrap().unwrap();
+
+        let diagnostic = &migration.diagnostics[0];
+        assert_eq!(diagnostic.code, "UnparseableLegacyDoc");
+        assert_eq!(
+            &source[diagnostic.position.start..diagnostic.position.end],
+            "Anything else is ignored"
+        );
+        assert_eq!(
+            LiquidDoc::from_ast(&migration.ast).description_text(),
+            Some("Renders a badge\n  Anything else is ignored")
+        );
+    }
+
+    #[test]
+    fn normalizes_nil_unions_and_reports_other_unions() {
+        let source = "{% comment %}\n  Accepts:\n  - product: {Object | nil} The product\n  - size: {String | Number} The size\n{% endcomment %}";
+        let migration = migrate_legacy_doc(source).unwrap().unwrap();
+
+        assert_eq!(
+            migration.edit.new_text,
+            "{% doc %}\n  @param {object} [product] - The product\n  @param size - The size\n{% enddoc %}"
+        );
+        assert_eq!(migration.diagnostics.len(), 1);
+        let diagnostic = &migration.diagnostics[0];
+        assert_eq!(diagnostic.code, "UnrepresentableLegacyType");
+        assert_eq!(
+            &source[diagnostic.position.start..diagnostic.position.end],
+            "- size: {String | Number} The size"
+        );
+
+        let doc = LiquidDoc::from_ast(&migration.ast);
+        assert!(!doc.param("product").unwrap().required);
+        assert_eq!(doc.param("size").unwrap().param_type, None);
+        assert!(doc.unknown_tags.is_empty());
+    }
+
+    #[test]
+    fn reports_comments_that_cannot_be_migrated() {
+        let fenced = "{% comment %}\n  Accepts:\n  - a: {String}\n\n  Usage:\n  ```liquid\n  {% render 'badge', a: 'x' %}\n  ```\n{% endcomment %}";
+        let closing = "{% comment %}\n  Accepts:\n  - a: {String} Shown before {% enddoc %}\n  - b: {String}\n{% endcomment %}";
+
+        for source in [fenced, closing] {
+            let diagnostic = migrate_legacy_doc(source).unwrap().unwrap_err();
+            assert_eq!(diagnostic.code, "UnmigratableLegacyDoc");
+            assert_eq!(diagnostic.severity, crate::Severity::Error);
+            assert_eq!(
+                &source[diagnostic.position.start..diagnostic.position.end],
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn ignores_other_comments_and_documented_templates() {
+        assert_eq!(
+            migrate_legacy_doc("{% comment %}Just a note{% endcomment %}"),
+            None
+        );
+        assert_eq!(
+            migrate_legacy_doc("<div>{% comment %}\nAccepts:\n- a: {String}\n{% endcomment %}"),
+            None
+        );
+        assert_eq!(
+            migrate_legacy_doc(
+                "{% comment %}\nAccepts:\n- a: {String}\n{% endcomment %}{% doc %}{% enddoc %}"
+            ),
+            None
+        );
+    }
+}
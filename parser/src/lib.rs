@@ -1,8 +1,83 @@
 mod ast;
+#[cfg(feature = "binary")]
+mod binary;
+mod builder;
+mod content_for_calls;
+mod coverage;
+mod diagnostics;
+mod doc_stub;
+mod events;
+mod legacy_doc;
+mod liquid_doc;
+mod liquid_file;
+mod liquid_html;
+mod liquid_html_import;
+mod liquid_tags;
+mod param_usage;
 mod parser;
+mod render_calls;
+mod render_graph;
+#[cfg(feature = "schema")]
+mod schema;
+mod stream;
+mod theme_index;
+mod unused_files;
 
 // Re-export all AST types
 pub use ast::*;
 
+// Re-export the compact binary encoding
+#[cfg(feature = "binary")]
+pub use binary::*;
+
+// Re-export the doc builder
+pub use builder::*;
+
+// Re-export diagnostics
+pub use diagnostics::*;
+
+// Re-export the pull parser
+pub use events::{parse_doc_events, DocEvent, DocEvents};
+
+// Re-export the migration of comment-style docs
+pub use legacy_doc::{migrate_legacy_doc, LegacyDocMigration};
+
+// Re-export the semantic model
+pub use liquid_doc::*;
+
+// Re-export doc block extraction from whole templates
+pub use liquid_file::*;
+
+// Re-export the liquid-html-parser compatibility output and import
+pub use liquid_html::*;
+pub use liquid_html_import::*;
+
 // Re-export parser functionality
-pub use parser::{parse_liquid_string, visit, LiquidParser};
+pub use parser::{
+    parse_liquid_nodes, parse_liquid_string, parse_liquid_string_with_options, visit, LiquidParser,
+    ParseOptions,
+};
+
+// Re-export the streaming JSON writer
+pub use stream::*;
+
+// Re-export the theme index and the checks run on it
+pub use content_for_calls::{
+    check_content_for_calls, check_file_content_for_calls, parse_content_for_calls, ContentForCall,
+};
+pub use coverage::{doc_coverage, CoverageCounts, CoverageReport, DirectoryCoverage, FileCoverage};
+pub use doc_stub::{infer_doc_stub, DocStub, StubParam};
+pub use param_usage::{
+    check_param_usage, check_theme_param_usage, free_variables, UsageHint, VariableReference,
+};
+pub use render_calls::{
+    check_file_render_calls, check_render_calls, parse_render_calls, Argument, ArgumentValue,
+    BindingKind, RenderBinding, RenderCall, RenderTarget,
+};
+pub use render_graph::{EdgeKind, GraphEdge, GraphNode, RenderGraph};
+pub use theme_index::*;
+pub use unused_files::{find_unused_files, UnusedConfidence, UnusedFile};
+
+// Re-export the JSON Schema of the serialised AST
+#[cfg(feature = "schema")]
+pub use schema::*;
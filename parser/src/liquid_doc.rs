@@ -0,0 +1,353 @@
+use std::fmt;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{LiquidAST, LiquidNode, Position};
+
+/// Points back at the node of the `LiquidAST` a semantic item was derived from.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct NodeRef {
+    /// Index into `LiquidAST.nodes`.
+    pub index: usize,
+    pub position: Position,
+}
+
+impl NodeRef {
+    pub fn node<'a>(&self, ast: &'a LiquidAST) -> &'a LiquidNode {
+        &ast.nodes[self.index]
+    }
+}
+
+/// The declared type of a param, e.g. `string`, `product` or `number[]`.
+/// The built-in LiquidDoc types are matched case-insensitively.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(into = "String", from = "String")]
+pub enum ParamType {
+    String,
+    Number,
+    Boolean,
+    Object,
+    /// `array` has no item type, `string[]` does.
+    Array(Option<Box<ParamType>>),
+    /// Any other type, usually a Shopify object such as `product`.
+    Named(String),
+}
+
+impl ParamType {
+    pub fn parse(value: &str) -> Self {
+        let value = value.trim();
+        if let Some(item) = value.strip_suffix("[]") {
+            return ParamType::Array(Some(Box::new(ParamType::parse(item))));
+        }
+
+        match value.to_lowercase().as_str() {
+            "string" => ParamType::String,
+            "number" => ParamType::Number,
+            "boolean" => ParamType::Boolean,
+            "object" => ParamType::Object,
+            "array" => ParamType::Array(None),
+            _ => ParamType::Named(value.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ParamType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamType::String => write!(f, "string"),
+            ParamType::Number => write!(f, "number"),
+            ParamType::Boolean => write!(f, "boolean"),
+            ParamType::Object => write!(f, "object"),
+            ParamType::Array(None) => write!(f, "array"),
+            ParamType::Array(Some(item)) => write!(f, "{}[]", item),
+            ParamType::Named(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl From<ParamType> for String {
+    fn from(param_type: ParamType) -> Self {
+        param_type.to_string()
+    }
+}
+
+impl From<String> for ParamType {
+    fn from(value: String) -> Self {
+        ParamType::parse(&value)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DocDescription {
+    pub text: String,
+    /// The implicit description and every `@description`, in source order.
+    pub nodes: Vec<NodeRef>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DocParam {
+    pub name: String,
+    #[serde(rename = "paramType")]
+    pub param_type: Option<ParamType>,
+    pub required: bool,
+    pub description: Option<String>,
+    pub node: NodeRef,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DocExample {
+    pub content: String,
+    pub title: Option<String>,
+    pub language: Option<String>,
+    pub code: Option<String>,
+    pub node: NodeRef,
+}
+
+impl DocExample {
+    /// The fenced code when there is one, the whole content otherwise.
+    pub fn code_or_content(&self) -> &str {
+        self.code.as_deref().unwrap_or(&self.content)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DocPrompt {
+    pub content: String,
+    pub node: NodeRef,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DocUnknownTag {
+    pub name: String,
+    pub content: String,
+    pub node: NodeRef,
+}
+
+/// The facts a `LiquidAST` documents, without the syntax around them.
+///
+/// ```
+/// use liquid_doc_parser::{parse_liquid_string, LiquidDoc};
+///
+/// let ast = parse_liquid_string("@param {number} [image_width] - The width", None).unwrap();
+/// let doc = LiquidDoc::from_ast(&ast);
+/// assert!(!doc.param("image_width").unwrap().required);
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct LiquidDoc {
+    pub description: Option<DocDescription>,
+    /// Params in declaration order. When a name is declared twice, the first declaration wins.
+    pub params: IndexMap<String, DocParam>,
+    pub examples: Vec<DocExample>,
+    pub prompts: Vec<DocPrompt>,
+    #[serde(rename = "unknownTags")]
+    pub unknown_tags: Vec<DocUnknownTag>,
+}
+
+impl LiquidDoc {
+    pub fn from_ast(ast: &LiquidAST) -> Self {
+        let mut doc = LiquidDoc::default();
+        let mut descriptions = Vec::new();
+
+        for (index, node) in ast.nodes.iter().enumerate() {
+            match node {
+                LiquidNode::LiquidDocDescriptionNode(description) => {
+                    let node = NodeRef {
+                        index,
+                        position: description.position,
+                    };
+                    descriptions.push((description.value().trim().to_string(), node));
+                }
+                LiquidNode::LiquidDocParamNode(param) => {
+                    let name = text_value(&param.param_name).to_string();
+                    doc.params.entry(name.clone()).or_insert(DocParam {
+                        name,
+                        param_type: param
+                            .param_type
+                            .as_deref()
+                            .map(|t| ParamType::parse(text_value(t))),
+                        required: param.required,
+                        description: param
+                            .param_description
+                            .as_deref()
+                            .map(|d| text_value(d).trim().to_string()),
+                        node: NodeRef {
+                            index,
+                            position: param.position,
+                        },
+                    });
+                }
+                LiquidNode::LiquidDocExampleNode(example) => doc.examples.push(DocExample {
+                    content: text_value(&example.content).to_string(),
+                    title: example.title.as_deref().map(|t| text_value(t).to_string()),
+                    language: example
+                        .language
+                        .as_deref()
+                        .map(|l| text_value(l).to_string()),
+                    code: example.code.as_deref().map(|c| text_value(c).to_string()),
+                    node: NodeRef {
+                        index,
+                        position: example.position,
+                    },
+                }),
+                LiquidNode::LiquidDocUnknownTagNode(unknown_tag) => {
+                    doc.unknown_tags.push(DocUnknownTag {
+                        name: unknown_tag.name.clone(),
+                        content: unknown_tag.value().to_string(),
+                        node: NodeRef {
+                            index,
+                            position: unknown_tag.position,
+                        },
+                    })
+                }
+                LiquidNode::TextNode(text) => {
+                    if let Some(mut content) = text.prompt_content() {
+                        content.unescape();
+                        doc.prompts.push(DocPrompt {
+                            content: content.as_str().trim().to_string(),
+                            node: NodeRef {
+                                index,
+                                position: text.position,
+                            },
+                        });
+                    }
+                }
+            }
+        }
+
+        if !descriptions.is_empty() {
+            let text = descriptions
+                .iter()
+                .map(|(text, _)| text.as_str())
+                .filter(|text| !text.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let nodes = descriptions.into_iter().map(|(_, node)| node).collect();
+            doc.description = Some(DocDescription { text, nodes });
+        }
+
+        doc
+    }
+
+    pub fn param(&self, name: &str) -> Option<&DocParam> {
+        self.params.get(name)
+    }
+
+    pub fn required_params(&self) -> impl Iterator<Item = &DocParam> {
+        self.params.values().filter(|param| param.required)
+    }
+
+    pub fn description_text(&self) -> Option<&str> {
+        self.description.as_ref().map(|d| d.text.as_str())
+    }
+}
+
+impl From<&LiquidAST> for LiquidDoc {
+    fn from(ast: &LiquidAST) -> Self {
+        LiquidDoc::from_ast(ast)
+    }
+}
+
+fn text_value(node: &LiquidNode) -> &str {
+    match node {
+        LiquidNode::TextNode(text_node) => text_node.as_str(),
+        _ => unreachable!("Expected a TextNode"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_liquid_string;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn builds_params_in_declaration_order() {
+        let input = include_str!("../../web/fixtures/fixture1.liquid");
+        let ast = parse_liquid_string(input, None).unwrap();
+        let doc = LiquidDoc::from_ast(&ast);
+
+        let names: Vec<_> = doc.params.keys().map(String::as_str).collect();
+        assert_eq!(
+            names,
+            vec![
+                "resource",
+                "resource_type",
+                "collection_thumbnails",
+                "style",
+                "image_width",
+                "image_aspect_ratio",
+                "image_hover"
+            ]
+        );
+
+        let image_width = doc.param("image_width").unwrap();
+        assert_eq!(image_width.param_type, Some(ParamType::Number));
+        assert!(!image_width.required);
+        assert_eq!(
+            image_width.description.as_deref(),
+            Some("The width of the image")
+        );
+        assert!(matches!(
+            image_width.node.node(&ast),
+            LiquidNode::LiquidDocParamNode(node) if node.source.contains("image_width")
+        ));
+
+        let required: Vec<_> = doc.required_params().map(|p| p.name.as_str()).collect();
+        assert_eq!(required, vec!["resource", "resource_type"]);
+        assert_eq!(doc.examples.len(), 1);
+    }
+
+    #[test]
+    fn merges_implicit_and_explicit_descriptions() {
+        let input = include_str!("../../web/fixtures/fixture7.liquid");
+        let ast = parse_liquid_string(input, None).unwrap();
+        let doc = LiquidDoc::from_ast(&ast);
+
+        let description = doc.description.unwrap();
+        assert_eq!(
+            description.text,
+            "this is an implicit description\nin a header\nwith a description annotation"
+        );
+        assert_eq!(description.nodes.len(), 2);
+    }
+
+    #[test]
+    fn collects_prompts_examples_and_unknown_tags() {
+        let input = "@deprecated use card-v2\n@prompt\nWrite a card\n@example Card\n```liquid\n{% render 'card' %}\n```";
+        let ast = parse_liquid_string(input, None).unwrap();
+        let doc = LiquidDoc::from_ast(&ast);
+
+        assert_eq!(doc.prompts.len(), 1);
+        assert_eq!(doc.prompts[0].content, "Write a card");
+        assert_eq!(doc.examples[0].title.as_deref(), Some("Card"));
+        assert_eq!(doc.examples[0].language.as_deref(), Some("liquid"));
+        assert_eq!(doc.examples[0].code_or_content(), "{% render 'card' %}");
+        assert_eq!(doc.unknown_tags[0].name, "deprecated");
+        assert_eq!(doc.unknown_tags[0].content, "use card-v2");
+        assert!(doc.description.is_none());
+    }
+
+    #[test]
+    fn first_declaration_of_a_param_wins() {
+        let input = "@param {string} title - First\n@param {number} title - Second";
+        let doc = LiquidDoc::from_ast(&parse_liquid_string(input, None).unwrap());
+
+        assert_eq!(doc.params.len(), 1);
+        assert_eq!(
+            doc.param("title").unwrap().param_type,
+            Some(ParamType::String)
+        );
+    }
+
+    #[test]
+    fn resolves_param_types() {
+        assert_eq!(ParamType::parse("String"), ParamType::String);
+        assert_eq!(ParamType::parse("array"), ParamType::Array(None));
+        assert_eq!(
+            ParamType::parse("product[]"),
+            ParamType::Array(Some(Box::new(ParamType::Named("product".to_string()))))
+        );
+        assert_eq!(ParamType::parse("number[]").to_string(), "number[]");
+    }
+}
@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{LiquidAST, Position};
+use crate::diagnostics::Diagnostic;
+use crate::liquid_tags::LiquidTags;
+use crate::parser::{parse_liquid_string_with_options, ParseOptions};
+
+/// A `{% doc %}` block found in a template.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DocBlock {
+    /// From the `{%` of the opening tag to the `%}` of `{% enddoc %}`.
+    pub position: Position,
+    /// The content between the tags, which the AST was parsed from.
+    #[serde(rename = "bodyPosition")]
+    pub body_position: Position,
+    pub ast: LiquidAST,
+}
+
+/// The `{% doc %}` blocks of a whole template, in source order.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct LiquidFile {
+    #[serde(rename = "docBlocks")]
+    pub doc_blocks: Vec<DocBlock>,
+    /// Blocks that could not be parsed, e.g. a `{% doc %}` without `{% enddoc %}`.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl LiquidFile {
+    /// The first doc block, which is the one tooling reads for a snippet or block.
+    pub fn doc(&self) -> Option<&DocBlock> {
+        self.doc_blocks.first()
+    }
+}
+
+pub fn parse_liquid_file(input: &str) -> LiquidFile {
+    parse_liquid_file_with_options(input, &ParseOptions::new())
+}
+
+/// Finds every `{% doc %}...{% enddoc %}` block of a template, including the whitespace
+/// control `{%- doc -%}` forms, and parses each body with the offset of that body.
+///
+/// Blocks inside `{% raw %}` and `{% comment %}` are skipped. `position_offset` in `options` is
+/// the offset of the template itself and is added to every position.
+///
+/// ```
+/// use liquid_doc_parser::parse_liquid_file;
+///
+/// let input = "{% doc %}\n  @param {string} title\n{% enddoc %}\n<h1>{{ title }}</h1>";
+/// let file = parse_liquid_file(input);
+/// let block = file.doc().unwrap();
+/// assert_eq!(block.body_position.start, 9);
+/// assert_eq!(block.ast.position_offset, 9);
+/// ```
+pub fn parse_liquid_file_with_options(input: &str, options: &ParseOptions) -> LiquidFile {
+    let offset = options.position_offset.unwrap_or(0);
+    let mut file = LiquidFile::default();
+
+    for tag in LiquidTags::new(input).filter(|tag| tag.name == "doc") {
+        let Some((end_start, end_end)) = tag.closing else {
+            file.diagnostics.push(Diagnostic::error(
+                "UnclosedDocBlock",
+                "This {% doc %} block is missing its {% enddoc %}".to_string(),
+                Position::new(tag.start, tag.end, Some(offset)),
+            ));
+            continue;
+        };
+
+        let body = &input[tag.end..end_start];
+        let body_options = options.clone().with_position_offset(Some(offset + tag.end));
+        let position = Position::new(tag.start, end_end, Some(offset));
+        match parse_liquid_string_with_options(body, &body_options) {
+            Some(ast) => file.doc_blocks.push(DocBlock {
+                position,
+                body_position: Position::new(tag.end, end_start, Some(offset)),
+                ast,
+            }),
+            None => file.diagnostics.push(Diagnostic::error(
+                "UnparseableDocBlock",
+                "The content of this {% doc %} block could not be parsed".to_string(),
+                position,
+            )),
+        }
+    }
+
+    file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::LiquidNode;
+    use pretty_assertions::assert_eq;
+
+    fn bodies<'a>(input: &'a str, file: &LiquidFile) -> Vec<&'a str> {
+        file.doc_blocks
+            .iter()
+            .map(|block| &input[block.body_position.start..block.body_position.end])
+            .collect()
+    }
+
+    #[test]
+    fn parses_doc_blocks_with_their_offsets() {
+        let input = "<div>\n{% doc %}\n  Renders a card\n  @param {string} title\n{% enddoc %}\n{{ title }}\n</div>";
+        let file = parse_liquid_file(input);
+
+        assert_eq!(file.diagnostics, vec![]);
+        let block = file.doc().unwrap();
+        assert_eq!(
+            &input[block.position.start..block.position.end],
+            "{% doc %}\n  Renders a card\n  @param {string} title\n{% enddoc %}"
+        );
+        let body = &input[block.body_position.start..block.body_position.end];
+        assert_eq!(block.ast.validate(body, Some(15)), Ok(()));
+
+        let LiquidNode::LiquidDocParamNode(param) = &block.ast.nodes[1] else {
+            panic!("Expected a param");
+        };
+        assert_eq!(
+            &input[param.position.start..param.position.end],
+            "@param {string} title"
+        );
+    }
+
+    #[test]
+    fn finds_whitespace_control_tags() {
+        let input = "{%- doc -%}\n@param {string} title\n{%-enddoc-%}";
+        let file = parse_liquid_file(input);
+
+        assert_eq!(bodies(input, &file), vec!["\n@param {string} title\n"]);
+        assert_eq!(
+            file.doc().unwrap().position,
+            Position::new(0, input.len(), None)
+        );
+    }
+
+    #[test]
+    fn skips_raw_and_comments() {
+        let input = concat!(
+            "{% raw %}{% doc %}@param {string} a{% enddoc %}{% endraw %}",
+            "{% comment %}{% comment %}{% endcomment %}{% doc %}@param {string} b{% enddoc %}{% endcomment %}",
+            "{% # doc %}",
+            "{% doc %}@param {string} c{% enddoc %}",
+        );
+        let file = parse_liquid_file(input);
+
+        assert_eq!(bodies(input, &file), vec!["@param {string} c"]);
+    }
+
+    #[test]
+    fn reports_unclosed_doc_blocks() {
+        let input = "{% doc %}@param {string} a{% enddoc %}\n{% doc %}@param {string} b";
+        let file = parse_liquid_file(input);
+
+        assert_eq!(file.doc_blocks.len(), 1);
+        assert_eq!(file.diagnostics[0].code, "UnclosedDocBlock");
+        assert_eq!(file.diagnostics[0].position, Position::new(39, 48, None));
+    }
+
+    #[test]
+    fn offsets_add_up() {
+        let input = "{% doc %}Hello{% enddoc %}";
+        let file = parse_liquid_file_with_options(
+            input,
+            &ParseOptions::new().with_position_offset(Some(100)),
+        );
+        let block = file.doc().unwrap();
+
+        assert_eq!(block.position, Position::new(100, 126, None));
+        assert_eq!(block.ast.position_offset, 109);
+        assert_eq!(block.ast.validate("Hello", Some(109)), Ok(()));
+    }
+}
@@ -0,0 +1,469 @@
+//! Serialises a `LiquidAST` with the exact node shapes liquid-html-parser gives the children of a
+//! `{% doc %}` tag, so this parser can back prettier-plugin-liquid and theme-check-js.
+
+use serde::Serialize;
+
+use crate::ast::{
+    LiquidAST, LiquidDocDescriptionNode, LiquidDocExampleNode, LiquidDocParamNode, LiquidNode,
+    Position, TextNode,
+};
+
+/// Which liquid-html-parser stage to mirror.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum LiquidHtmlShape {
+    /// `toLiquidHtmlAST`: `position` objects, `paramName` as a `TextNode` and `required` on the param.
+    #[default]
+    Ast,
+    /// `toLiquidHtmlCST`: `locStart`/`locEnd`, and `paramName` as a `LiquidDocParamNameNode`.
+    Concrete,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum LiquidHtmlLocation {
+    Ast {
+        position: Position,
+    },
+    Concrete {
+        #[serde(rename = "locStart")]
+        loc_start: usize,
+        #[serde(rename = "locEnd")]
+        loc_end: usize,
+    },
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+#[allow(clippy::enum_variant_names)]
+pub enum LiquidHtmlNode<'a> {
+    TextNode {
+        value: String,
+        #[serde(flatten)]
+        location: LiquidHtmlLocation,
+        source: &'a str,
+    },
+    LiquidDocDescriptionNode {
+        name: &'static str,
+        #[serde(flatten)]
+        location: LiquidHtmlLocation,
+        source: &'a str,
+        content: Box<LiquidHtmlNode<'a>>,
+        #[serde(rename = "isImplicit")]
+        is_implicit: bool,
+        #[serde(rename = "isInline")]
+        is_inline: bool,
+    },
+    LiquidDocParamNode {
+        name: &'static str,
+        #[serde(flatten)]
+        location: LiquidHtmlLocation,
+        source: &'a str,
+        #[serde(rename = "paramName")]
+        param_name: Box<LiquidHtmlNode<'a>>,
+        #[serde(rename = "paramDescription")]
+        param_description: Option<Box<LiquidHtmlNode<'a>>>,
+        #[serde(rename = "paramType")]
+        param_type: Option<Box<LiquidHtmlNode<'a>>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        required: Option<bool>,
+    },
+    LiquidDocParamNameNode {
+        #[serde(flatten)]
+        location: LiquidHtmlLocation,
+        source: &'a str,
+        content: Box<LiquidHtmlNode<'a>>,
+        required: bool,
+    },
+    LiquidDocExampleNode {
+        name: &'static str,
+        #[serde(flatten)]
+        location: LiquidHtmlLocation,
+        source: &'a str,
+        content: Box<LiquidHtmlNode<'a>>,
+        #[serde(rename = "isInline")]
+        is_inline: bool,
+    },
+    LiquidDocPromptNode {
+        name: &'static str,
+        #[serde(flatten)]
+        location: LiquidHtmlLocation,
+        source: &'a str,
+        content: Box<LiquidHtmlNode<'a>>,
+    },
+}
+
+/// Converts the nodes of `ast` to liquid-html-parser's shapes.
+///
+/// liquid-html-parser sets every node's `source` to the whole template, so pass the text the
+/// positions point into: the full `.liquid` file when the AST was parsed with a `position_offset`.
+/// Values are taken from the raw source, since liquid-html-parser has no escape sequences.
+pub fn to_liquid_html_nodes<'a>(
+    ast: &LiquidAST,
+    document_source: &'a str,
+    shape: LiquidHtmlShape,
+) -> Vec<LiquidHtmlNode<'a>> {
+    let builder = Builder {
+        source: document_source,
+        shape,
+    };
+    ast.nodes
+        .iter()
+        .flat_map(|node| builder.node(node))
+        .collect()
+}
+
+/// Like [`to_liquid_html_nodes`], serialised to a JSON array.
+pub fn to_liquid_html_json(
+    ast: &LiquidAST,
+    document_source: &str,
+    shape: LiquidHtmlShape,
+) -> serde_json::Result<String> {
+    serde_json::to_string(&to_liquid_html_nodes(ast, document_source, shape))
+}
+
+struct Builder<'a> {
+    source: &'a str,
+    shape: LiquidHtmlShape,
+}
+
+impl<'a> Builder<'a> {
+    fn location(&self, start: usize, end: usize) -> LiquidHtmlLocation {
+        match self.shape {
+            LiquidHtmlShape::Ast => LiquidHtmlLocation::Ast {
+                position: Position::new(start, end, None),
+            },
+            LiquidHtmlShape::Concrete => LiquidHtmlLocation::Concrete {
+                loc_start: start,
+                loc_end: end,
+            },
+        }
+    }
+
+    fn text(&self, value: &str, start: usize) -> LiquidHtmlNode<'a> {
+        LiquidHtmlNode::TextNode {
+            value: value.to_string(),
+            location: self.location(start, start + value.len()),
+            source: self.source,
+        }
+    }
+
+    fn node(&self, node: &LiquidNode) -> Vec<LiquidHtmlNode<'a>> {
+        match node {
+            LiquidNode::TextNode(text) => match text.prompt_content() {
+                Some(content) => vec![LiquidHtmlNode::LiquidDocPromptNode {
+                    name: "prompt",
+                    location: self.location(text.position.start, text.position.end),
+                    source: self.source,
+                    content: Box::new(self.text(&content.source, content.position.start)),
+                }],
+                None => self.split_text(&text.source, text.position.start),
+            },
+            LiquidNode::LiquidDocDescriptionNode(description) if description.is_implicit => {
+                self.implicit_description(description)
+            }
+            LiquidNode::LiquidDocDescriptionNode(description) => {
+                let (content_start, is_inline) = tag_body(&description.source, "@description");
+                vec![LiquidHtmlNode::LiquidDocDescriptionNode {
+                    name: "description",
+                    location: self.location(description.position.start, description.position.end),
+                    source: self.source,
+                    content: Box::new(self.text(
+                        &description.source[content_start..],
+                        description.position.start + content_start,
+                    )),
+                    is_implicit: false,
+                    is_inline,
+                }]
+            }
+            LiquidNode::LiquidDocParamNode(param) => vec![self.param(param)],
+            LiquidNode::LiquidDocExampleNode(example) => vec![self.example(example)],
+            LiquidNode::LiquidDocUnknownTagNode(unknown_tag) => {
+                vec![self.text(&unknown_tag.source, unknown_tag.position.start)]
+            }
+        }
+    }
+
+    /// liquid-html-parser ends the implicit description at the first `@`, tag or not.
+    fn implicit_description(
+        &self,
+        description: &LiquidDocDescriptionNode,
+    ) -> Vec<LiquidHtmlNode<'a>> {
+        let source = description.source.as_str();
+        let start = description.position.start;
+        let end = source
+            .find('@')
+            .map(|at| source[..at].trim_end_matches(is_strict_space).len())
+            .unwrap_or(source.len());
+
+        let mut nodes = Vec::new();
+        if end > 0 {
+            nodes.push(LiquidHtmlNode::LiquidDocDescriptionNode {
+                name: "description",
+                location: self.location(start, start + end),
+                source: self.source,
+                content: Box::new(self.text(&source[..end], start)),
+                is_implicit: true,
+                is_inline: true,
+            });
+        }
+        nodes.extend(self.split_text(&source[end..], start + end));
+        nodes
+    }
+
+    fn param(&self, param: &LiquidDocParamNode) -> LiquidHtmlNode<'a> {
+        let param_type = param.param_type.as_deref().and_then(|param_type| {
+            let param_type = as_text(param_type);
+            // The text node spans the braces' content
+            let inner = param_type.source.as_str();
+            let value = inner.trim_matches(is_strict_space);
+            let leading = inner.len() - inner.trim_start_matches(is_strict_space).len();
+            let node = self.text(value, param_type.position.start + leading);
+            match (self.shape, value.is_empty()) {
+                (LiquidHtmlShape::Ast, true) => None,
+                _ => Some(Box::new(node)),
+            }
+        });
+
+        let param_name = as_text(&param.param_name);
+        let (name_start, name_end) = if param.required {
+            (param_name.position.start, param_name.position.end)
+        } else {
+            (param_name.position.start - 1, param_name.position.end + 1)
+        };
+        // The text node spans the brackets' content
+        let name_source = &param_name.source;
+        let name = name_source.trim_matches(is_strict_space);
+        let name_offset = name_source.find(name).unwrap_or(0);
+        let name_text = self.text(name, param_name.position.start + name_offset);
+
+        let param_description = match param.param_description.as_deref() {
+            Some(description) => {
+                let description = as_text(description);
+                Some(Box::new(
+                    self.text(&description.source, description.position.start),
+                ))
+            }
+            None if self.shape == LiquidHtmlShape::Concrete => {
+                Some(Box::new(self.text("", param.position.end)))
+            }
+            None => None,
+        };
+
+        let (param_name, required) = match self.shape {
+            LiquidHtmlShape::Ast => (Box::new(name_text), Some(param.required)),
+            LiquidHtmlShape::Concrete => (
+                Box::new(LiquidHtmlNode::LiquidDocParamNameNode {
+                    location: self.location(name_start, name_end),
+                    source: self.source,
+                    content: Box::new(name_text),
+                    required: param.required,
+                }),
+                None,
+            ),
+        };
+
+        LiquidHtmlNode::LiquidDocParamNode {
+            name: "param",
+            location: self.location(param.position.start, param.position.end),
+            source: self.source,
+            param_name,
+            param_description,
+            param_type,
+            required,
+        }
+    }
+
+    fn example(&self, example: &LiquidDocExampleNode) -> LiquidHtmlNode<'a> {
+        let (content_start, is_inline) = tag_body(&example.source, "@example");
+        LiquidHtmlNode::LiquidDocExampleNode {
+            name: "example",
+            location: self.location(example.position.start, example.position.end),
+            source: self.source,
+            content: Box::new(self.text(
+                &example.source[content_start..],
+                example.position.start + content_start,
+            )),
+            is_inline,
+        }
+    }
+
+    /// Splits text the way liquid-html-parser does: every `@` starts a fallback text node that
+    /// runs to the end of its line, and whitespace between nodes is skipped.
+    fn split_text(&self, text: &str, start: usize) -> Vec<LiquidHtmlNode<'a>> {
+        let mut nodes = Vec::new();
+        let mut index = 0;
+        while index < text.len() {
+            let rest = &text[index..];
+            let trimmed = rest.trim_start();
+            index += rest.len() - trimmed.len();
+            if trimmed.is_empty() {
+                break;
+            }
+
+            let len = if trimmed.starts_with('@') {
+                let line = trimmed.split(['\n', '\r']).next().unwrap_or(trimmed);
+                line.trim_end_matches(is_strict_space).len()
+            } else {
+                match trimmed.find('@') {
+                    Some(at) => trimmed[..at].trim_end_matches(is_strict_space).len(),
+                    None => trimmed.trim_end_matches(is_strict_space).len(),
+                }
+            };
+
+            nodes.push(self.text(&text[index..index + len], start + index));
+            index += len;
+        }
+        nodes
+    }
+}
+
+/// Returns where the content after `tag` and its leading whitespace starts, and whether that
+/// whitespace stays on the tag's line.
+fn tag_body(source: &str, tag: &str) -> (usize, bool) {
+    let after_tag = &source[tag.len()..];
+    let content = after_tag.trim_start();
+    let whitespace = &after_tag[..after_tag.len() - content.len()];
+    (source.len() - content.len(), !whitespace.contains('\n'))
+}
+
+fn as_text(node: &LiquidNode) -> &TextNode {
+    match node {
+        LiquidNode::TextNode(text_node) => text_node,
+        _ => unreachable!("Expected a TextNode"),
+    }
+}
+
+fn is_strict_space(c: char) -> bool {
+    c == ' ' || c == '\t'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_liquid_string;
+    use pretty_assertions::assert_eq;
+    use serde_json::{json, Value};
+
+    const DOC_OPEN: &str = "{% doc %}";
+
+    fn liquid_html(input: &str, shape: LiquidHtmlShape) -> (String, Value) {
+        let template = format!("{}{}{{% enddoc %}}", DOC_OPEN, input);
+        let ast = parse_liquid_string(input, Some(DOC_OPEN.len())).unwrap();
+        let json = to_liquid_html_json(&ast, &template, shape).unwrap();
+        (template, serde_json::from_str(&json).unwrap())
+    }
+
+    #[test]
+    fn params_match_to_liquid_html_ast() {
+        let input = "@param {String} [optionalParameterWithType]\n@param requiredParamWithNoType - described";
+        let (template, nodes) = liquid_html(input, LiquidHtmlShape::Ast);
+
+        assert_eq!(
+            nodes[0],
+            json!({
+                "type": "LiquidDocParamNode",
+                "name": "param",
+                "position": { "start": 9, "end": 52 },
+                "source": template,
+                "paramName": {
+                    "type": "TextNode",
+                    "value": "optionalParameterWithType",
+                    "position": { "start": 26, "end": 51 },
+                    "source": template,
+                },
+                "paramDescription": null,
+                "paramType": {
+                    "type": "TextNode",
+                    "value": "String",
+                    "position": { "start": 17, "end": 23 },
+                    "source": template,
+                },
+                "required": false,
+            })
+        );
+        assert_eq!(nodes[1]["paramDescription"]["value"], "described");
+        assert_eq!(nodes[1]["paramType"], Value::Null);
+        assert_eq!(nodes[1]["required"], true);
+    }
+
+    #[test]
+    fn param_names_match_to_liquid_html_cst() {
+        let input = "@param {String} [ optional ]";
+        let (template, nodes) = liquid_html(input, LiquidHtmlShape::Concrete);
+
+        assert_eq!(
+            nodes[0]["paramName"],
+            json!({
+                "type": "LiquidDocParamNameNode",
+                "locStart": 25,
+                "locEnd": 37,
+                "source": template,
+                "content": {
+                    "type": "TextNode",
+                    "value": "optional",
+                    "locStart": 27,
+                    "locEnd": 35,
+                    "source": template,
+                },
+                "required": false,
+            })
+        );
+        assert_eq!(nodes[0]["paramDescription"]["value"], "");
+        assert_eq!(nodes[0].get("required"), None);
+    }
+
+    #[test]
+    fn prompts_are_prompt_nodes() {
+        let input = "@prompt\n  First prompt\n";
+        let (_, nodes) = liquid_html(input, LiquidHtmlShape::Ast);
+
+        assert_eq!(nodes[0]["type"], "LiquidDocPromptNode");
+        assert_eq!(nodes[0]["name"], "prompt");
+        assert_eq!(nodes[0]["content"]["value"], "\n  First prompt\n");
+        assert_eq!(nodes[0]["content"]["position"]["start"], 16);
+    }
+
+    #[test]
+    fn examples_and_descriptions_skip_all_leading_whitespace() {
+        let input = "@example  \n  {% render 'card' %}\n@description   Renders a card";
+        let (_, nodes) = liquid_html(input, LiquidHtmlShape::Ast);
+
+        assert_eq!(nodes[0]["content"]["value"], "{% render 'card' %}\n");
+        assert_eq!(nodes[0]["isInline"], false);
+        assert_eq!(nodes[1]["content"]["value"], "Renders a card");
+        assert_eq!(nodes[1]["isInline"], true);
+        assert_eq!(nodes[1]["isImplicit"], false);
+    }
+
+    #[test]
+    fn unknown_tags_and_bare_at_signs_become_text_nodes() {
+        let input = "Mail me@ home\n@unsupported this falls back";
+        let (_, nodes) = liquid_html(input, LiquidHtmlShape::Ast);
+        let types_and_values: Vec<_> = nodes
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|node| {
+                let value = node.get("value").or(node["content"].get("value"));
+                (node["type"].clone(), value.cloned().unwrap())
+            })
+            .collect();
+
+        assert_eq!(
+            types_and_values,
+            vec![
+                (json!("LiquidDocDescriptionNode"), json!("Mail me")),
+                (json!("TextNode"), json!("@ home")),
+                (json!("TextNode"), json!("@unsupported this falls back")),
+            ]
+        );
+    }
+
+    #[test]
+    fn values_keep_escapes() {
+        let input = "@param {string} title - Uses \\@media";
+        let (_, nodes) = liquid_html(input, LiquidHtmlShape::Ast);
+
+        assert_eq!(nodes[0]["paramDescription"]["value"], "Uses \\@media");
+    }
+}
@@ -0,0 +1,518 @@
+//! Reads the LiquidDoc nodes liquid-html-parser produces, as stored JSON, back into a `LiquidAST`.
+
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use crate::ast::{
+    LiquidAST, LiquidDocDescriptionNode, LiquidDocExampleNode, LiquidDocParamNode, LiquidNode,
+    Position, TextNode,
+};
+use crate::diagnostics::Diagnostic;
+
+/// Fields every liquid-html-parser node may carry.
+const COMMON_FIELDS: [&str; 5] = ["type", "position", "locStart", "locEnd", "source"];
+
+#[derive(Debug)]
+pub enum LiquidHtmlImportError {
+    Json(serde_json::Error),
+    /// A node lacks something a `LiquidAST` node cannot do without, e.g. its position.
+    InvalidNode {
+        path: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for LiquidHtmlImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LiquidHtmlImportError::Json(error) => write!(f, "Invalid JSON: {}", error),
+            LiquidHtmlImportError::InvalidNode { path, message } => {
+                write!(f, "Invalid node at {}: {}", path, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LiquidHtmlImportError {}
+
+impl From<serde_json::Error> for LiquidHtmlImportError {
+    fn from(error: serde_json::Error) -> Self {
+        LiquidHtmlImportError::Json(error)
+    }
+}
+
+type ImportResult<T> = Result<T, LiquidHtmlImportError>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiquidHtmlImport {
+    pub ast: LiquidAST,
+    /// What was dropped because `LiquidAST` cannot represent it: `UnrepresentableField`
+    /// for unknown fields and `UnsupportedNode` for unknown node types.
+    pub warnings: Vec<Diagnostic>,
+}
+
+/// Converts liquid-html-parser output into a `LiquidAST`.
+///
+/// Accepts the `{% doc %}` `LiquidRawTag`, its `RawMarkup` body, or the array of body nodes,
+/// in either the `toLiquidHtmlAST` or the `toLiquidHtmlCST` shape. Values are kept as given,
+/// so compare against this parser's output with `ParseOptions::with_fallback_text_nodes`,
+/// which also keeps unknown tags as text.
+///
+/// ```
+/// use liquid_doc_parser::import_liquid_html_json;
+///
+/// let json = r#"[{
+///     "type": "LiquidDocPromptNode", "name": "prompt",
+///     "position": { "start": 9, "end": 29 },
+///     "source": "{% doc %}@prompt Write a card{% enddoc %}",
+///     "content": { "type": "TextNode", "value": " Write a card", "position": { "start": 16, "end": 29 } }
+/// }]"#;
+/// let import = import_liquid_html_json(json).unwrap();
+/// assert_eq!(import.ast.position_offset, 9);
+/// assert!(import.warnings.is_empty());
+/// ```
+pub fn import_liquid_html_json(json: &str) -> ImportResult<LiquidHtmlImport> {
+    import_liquid_html_value(&serde_json::from_str(json)?)
+}
+
+/// Like [`import_liquid_html_json`], for JSON that is already parsed.
+pub fn import_liquid_html_value(value: &Value) -> ImportResult<LiquidHtmlImport> {
+    let (nodes, body_position) = doc_body(value)?;
+    let mut importer = Importer::default();
+
+    let mut imported = Vec::new();
+    for (index, node) in nodes.iter().enumerate() {
+        if let Some(node) = importer.node(node, &format!("nodes[{}]", index))? {
+            imported.push(node);
+        }
+    }
+
+    let body_position = match body_position {
+        Some(position) => position,
+        None => span(&imported),
+    };
+    let mut ast = LiquidAST::for_source(
+        body_position.end - body_position.start,
+        Some(body_position.start),
+    );
+    ast.nodes = imported;
+
+    Ok(LiquidHtmlImport {
+        ast,
+        warnings: importer.warnings,
+    })
+}
+
+/// Finds the body nodes and, when given, the position of the body they fill.
+fn doc_body(value: &Value) -> ImportResult<(&Vec<Value>, Option<Position>)> {
+    match value {
+        Value::Array(nodes) => Ok((nodes, None)),
+        Value::Object(object) => match object.get("type").and_then(Value::as_str) {
+            Some("LiquidRawTag") => doc_body(required(object, "body", "body")?),
+            Some("RawMarkup") => {
+                let nodes = required(object, "nodes", "body")?
+                    .as_array()
+                    .ok_or_else(|| invalid("body.nodes", "Expected an array"))?;
+                Ok((nodes, Some(position(object, "body")?)))
+            }
+            _ => Err(invalid(
+                "root",
+                "Expected a doc LiquidRawTag, its RawMarkup body or an array of nodes",
+            )),
+        },
+        _ => Err(invalid("root", "Expected an object or an array")),
+    }
+}
+
+#[derive(Default)]
+struct Importer {
+    warnings: Vec<Diagnostic>,
+}
+
+impl Importer {
+    fn node(&mut self, value: &Value, path: &str) -> ImportResult<Option<LiquidNode>> {
+        let object = as_object(value, path)?;
+        let node_type = required(object, "type", path)?
+            .as_str()
+            .ok_or_else(|| invalid(path, "Expected `type` to be a string"))?;
+        let position = position(object, path)?;
+
+        let node = match node_type {
+            "TextNode" => LiquidNode::TextNode(self.text(value, path)?),
+            "LiquidDocDescriptionNode" => {
+                self.check_fields(object, &["name", "content", "isImplicit", "isInline"], path);
+                LiquidNode::LiquidDocDescriptionNode(LiquidDocDescriptionNode {
+                    name: "description".to_string(),
+                    position,
+                    source: source(object, position),
+                    content: Box::new(LiquidNode::TextNode(self.content(object, path)?)),
+                    is_implicit: bool_field(object, "isImplicit", path)?.unwrap_or(false),
+                    is_inline: bool_field(object, "isInline", path)?.unwrap_or(true),
+                })
+            }
+            "LiquidDocParamNode" => LiquidNode::LiquidDocParamNode(self.param(object, path)?),
+            "LiquidDocExampleNode" => {
+                self.check_fields(object, &["name", "content", "isInline"], path);
+                let example = LiquidDocExampleNode::new(
+                    self.content(object, path)?,
+                    bool_field(object, "isInline", path)?.unwrap_or(true),
+                    position,
+                    source(object, position),
+                );
+                LiquidNode::LiquidDocExampleNode(example.with_body_parts())
+            }
+            // This parser keeps prompts as text nodes that include the tag
+            "LiquidDocPromptNode" => {
+                self.check_fields(object, &["name", "content"], path);
+                let content = self.content(object, path)?;
+                let mut source = source(object, position);
+                if source.is_empty() {
+                    source = format!("@prompt{}", content.value);
+                }
+                LiquidNode::TextNode(TextNode::new(source.clone(), position, source))
+            }
+            other => {
+                self.warnings.push(Diagnostic::warning(
+                    "UnsupportedNode",
+                    format!(
+                        "Skipped `{}` at {}, it has no LiquidAST equivalent",
+                        other, path
+                    ),
+                    position,
+                ));
+                return Ok(None);
+            }
+        };
+
+        Ok(Some(node))
+    }
+
+    fn param(
+        &mut self,
+        object: &Map<String, Value>,
+        path: &str,
+    ) -> ImportResult<LiquidDocParamNode> {
+        self.check_fields(
+            object,
+            &[
+                "name",
+                "paramName",
+                "paramDescription",
+                "paramType",
+                "required",
+            ],
+            path,
+        );
+        let position = position(object, path)?;
+
+        // `toLiquidHtmlCST` wraps the name in a LiquidDocParamNameNode that knows if it's required
+        let name_path = format!("{}.paramName", path);
+        let name_value = required(object, "paramName", path)?;
+        let name_object = as_object(name_value, &name_path)?;
+        let (param_name, name_required) = match name_object.get("type").and_then(Value::as_str) {
+            Some("LiquidDocParamNameNode") => {
+                self.check_fields(name_object, &["content", "required"], &name_path);
+                // The parser's name node spans the name only, so the brackets in the
+                // wrapper's source are dropped rather than copied over
+                let param_name = self.content(name_object, &name_path)?;
+                (param_name, bool_field(name_object, "required", &name_path)?)
+            }
+            _ => (self.text(name_value, &name_path)?, None),
+        };
+
+        let param_type = self.optional_text(object, "paramType", path)?;
+        let param_description = self
+            .optional_text(object, "paramDescription", path)?
+            .filter(|description| !description.is_empty());
+        let required = name_required
+            .or(bool_field(object, "required", path)?)
+            .unwrap_or(true);
+
+        Ok(LiquidDocParamNode {
+            name: "param".to_string(),
+            position,
+            source: source(object, position),
+            param_name: Box::new(LiquidNode::TextNode(param_name)),
+            param_description: param_description.map(|d| Box::new(LiquidNode::TextNode(d))),
+            param_type: param_type.map(|t| Box::new(LiquidNode::TextNode(t))),
+            required,
+        })
+    }
+
+    fn text(&mut self, value: &Value, path: &str) -> ImportResult<TextNode> {
+        let object = as_object(value, path)?;
+        if object.get("type").and_then(Value::as_str) != Some("TextNode") {
+            return Err(invalid(path, "Expected a TextNode"));
+        }
+        self.check_fields(object, &["value"], path);
+
+        let position = position(object, path)?;
+        let text = required(object, "value", path)?
+            .as_str()
+            .ok_or_else(|| invalid(path, "Expected `value` to be a string"))?;
+        let source = match source(object, position) {
+            source if source.is_empty() => text.to_string(),
+            source => source,
+        };
+        Ok(TextNode::new(text.to_string(), position, source))
+    }
+
+    fn content(&mut self, object: &Map<String, Value>, path: &str) -> ImportResult<TextNode> {
+        self.text(
+            required(object, "content", path)?,
+            &format!("{}.content", path),
+        )
+    }
+
+    fn optional_text(
+        &mut self,
+        object: &Map<String, Value>,
+        field: &str,
+        path: &str,
+    ) -> ImportResult<Option<TextNode>> {
+        match object.get(field) {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => self.text(value, &format!("{}.{}", path, field)).map(Some),
+        }
+    }
+
+    fn check_fields(&mut self, object: &Map<String, Value>, fields: &[&str], path: &str) {
+        let position = self::position(object, path).unwrap_or(Position::new(0, 0, None));
+        for field in object.keys() {
+            let field = field.as_str();
+            if COMMON_FIELDS.contains(&field) || fields.contains(&field) {
+                continue;
+            }
+            self.warnings.push(Diagnostic::warning(
+                "UnrepresentableField",
+                format!(
+                    "Dropped `{}` at {}, it has no LiquidAST equivalent",
+                    field, path
+                ),
+                position,
+            ));
+        }
+    }
+}
+
+/// Reads `position`, or `locStart`/`locEnd` in the concrete shape.
+fn position(object: &Map<String, Value>, path: &str) -> ImportResult<Position> {
+    let offsets = match object.get("position") {
+        Some(position) => (
+            position.get("start").and_then(Value::as_u64),
+            position.get("end").and_then(Value::as_u64),
+        ),
+        None => (
+            object.get("locStart").and_then(Value::as_u64),
+            object.get("locEnd").and_then(Value::as_u64),
+        ),
+    };
+
+    match offsets {
+        (Some(start), Some(end)) if start <= end => {
+            Ok(Position::new(start as usize, end as usize, None))
+        }
+        _ => Err(invalid(
+            path,
+            "Expected `position` or `locStart` and `locEnd`",
+        )),
+    }
+}
+
+/// liquid-html-parser sets `source` to the whole template, so cut out the node's own text.
+/// A `source` that is already just the node's text is kept as is.
+fn source(object: &Map<String, Value>, position: Position) -> String {
+    let Some(source) = object.get("source").and_then(Value::as_str) else {
+        return String::new();
+    };
+
+    match source.get(position.start..position.end) {
+        Some(slice) => slice.to_string(),
+        None if source.len() == position.end - position.start => source.to_string(),
+        None => String::new(),
+    }
+}
+
+fn span(nodes: &[LiquidNode]) -> Position {
+    let positions: Vec<Position> = nodes.iter().map(node_position).collect();
+    match (positions.first(), positions.last()) {
+        (Some(first), Some(last)) => Position::new(first.start, last.end, None),
+        _ => Position::new(0, 0, None),
+    }
+}
+
+fn node_position(node: &LiquidNode) -> Position {
+    match node {
+        LiquidNode::LiquidDocDescriptionNode(node) => node.position,
+        LiquidNode::TextNode(node) => node.position,
+        LiquidNode::LiquidDocParamNode(node) => node.position,
+        LiquidNode::LiquidDocExampleNode(node) => node.position,
+        LiquidNode::LiquidDocUnknownTagNode(node) => node.position,
+    }
+}
+
+fn as_object<'a>(value: &'a Value, path: &str) -> ImportResult<&'a Map<String, Value>> {
+    value
+        .as_object()
+        .ok_or_else(|| invalid(path, "Expected an object"))
+}
+
+fn required<'a>(
+    object: &'a Map<String, Value>,
+    field: &str,
+    path: &str,
+) -> ImportResult<&'a Value> {
+    object
+        .get(field)
+        .ok_or_else(|| invalid(path, &format!("Missing `{}`", field)))
+}
+
+fn bool_field(object: &Map<String, Value>, field: &str, path: &str) -> ImportResult<Option<bool>> {
+    match object.get(field) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Bool(value)) => Ok(Some(*value)),
+        Some(_) => Err(invalid(
+            path,
+            &format!("Expected `{}` to be a boolean", field),
+        )),
+    }
+}
+
+fn invalid(path: &str, message: &str) -> LiquidHtmlImportError {
+    LiquidHtmlImportError::InvalidNode {
+        path: path.to_string(),
+        message: message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::liquid_html::{to_liquid_html_json, LiquidHtmlShape};
+    use crate::parser::{parse_liquid_string_with_options, ParseOptions};
+    use crate::LiquidDoc;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    const DOC_OPEN: &str = "{% doc %}";
+
+    fn parse(input: &str) -> LiquidAST {
+        let options = ParseOptions::new()
+            .with_position_offset(Some(DOC_OPEN.len()))
+            .with_fallback_text_nodes(true);
+        parse_liquid_string_with_options(input, &options).unwrap()
+    }
+
+    fn round_trip(input: &str, shape: LiquidHtmlShape) -> LiquidHtmlImport {
+        let template = format!("{}{}{{% enddoc %}}", DOC_OPEN, input);
+        let json = to_liquid_html_json(&parse(input), &template, shape).unwrap();
+        import_liquid_html_json(&json).unwrap()
+    }
+
+    #[test]
+    fn imports_both_shapes_into_the_same_doc() {
+        let input = "Renders a card\n@param {string} title - The title\n@param {number} [width]\n@example Card\n```liquid\n{% render 'card' %}\n```\n@prompt\nWrite a card\n";
+        let expected = LiquidDoc::from_ast(&parse(input));
+
+        for shape in [LiquidHtmlShape::Ast, LiquidHtmlShape::Concrete] {
+            let import = round_trip(input, shape);
+            assert_eq!(import.warnings, vec![], "{:?}", shape);
+            assert_eq!(LiquidDoc::from_ast(&import.ast), expected, "{:?}", shape);
+            assert_eq!(import.ast.position_offset, DOC_OPEN.len());
+        }
+    }
+
+    /// Imported names must match the parser's, whose content excludes the brackets.
+    #[test]
+    fn optional_param_names_are_unwrapped() {
+        let import = round_trip("@param [width]", LiquidHtmlShape::Concrete);
+
+        match &import.ast.nodes[0] {
+            LiquidNode::LiquidDocParamNode(param) => {
+                assert!(!param.required);
+                assert!(param.param_description.is_none());
+                match param.param_name.as_ref() {
+                    LiquidNode::TextNode(name) => {
+                        assert_eq!(name.value, "width");
+                        assert_eq!(name.source, "width");
+                        assert_eq!(name.position, Position::new(17, 22, None));
+                    }
+                    _ => panic!("Expected a TextNode"),
+                }
+            }
+            _ => panic!("Expected a LiquidDocParamNode"),
+        }
+    }
+
+    #[test]
+    fn imports_the_doc_tag() {
+        let source = "{% doc %}Renders a card{% enddoc %}";
+        let value = json!({
+            "type": "LiquidRawTag",
+            "name": "doc",
+            "body": {
+                "type": "RawMarkup",
+                "position": { "start": 9, "end": 23 },
+                "source": source,
+                "nodes": [{
+                    "type": "LiquidDocDescriptionNode",
+                    "name": "description",
+                    "position": { "start": 9, "end": 23 },
+                    "source": source,
+                    "content": {
+                        "type": "TextNode",
+                        "value": "Renders a card",
+                        "position": { "start": 9, "end": 23 },
+                        "source": source,
+                    },
+                    "isImplicit": true,
+                    "isInline": true,
+                }],
+            },
+        });
+        let import = import_liquid_html_value(&value).unwrap();
+
+        assert_eq!(import.ast.position, Position::new(9, 23, None));
+        assert_eq!(
+            LiquidDoc::from_ast(&import.ast).description_text(),
+            Some("Renders a card")
+        );
+    }
+
+    #[test]
+    fn reports_what_cannot_be_represented() {
+        let value = json!([
+            {
+                "type": "TextNode",
+                "value": "@deprecated",
+                "position": { "start": 0, "end": 11 },
+                "blockStartPosition": { "start": 0, "end": 0 },
+            },
+            {
+                "type": "LiquidDocDeprecatedNode",
+                "position": { "start": 12, "end": 20 },
+            },
+        ]);
+        let import = import_liquid_html_value(&value).unwrap();
+
+        let codes: Vec<_> = import.warnings.iter().map(|d| d.code.as_str()).collect();
+        assert_eq!(codes, vec!["UnrepresentableField", "UnsupportedNode"]);
+        assert_eq!(
+            import.warnings[0].message,
+            "Dropped `blockStartPosition` at nodes[0], it has no LiquidAST equivalent"
+        );
+        assert_eq!(import.ast.nodes.len(), 1);
+    }
+
+    #[test]
+    fn nodes_without_positions_are_errors() {
+        let error =
+            import_liquid_html_json(r#"[{ "type": "TextNode", "value": "text" }]"#).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Invalid node at nodes[0]: Expected `position` or `locStart` and `locEnd`"
+        );
+    }
+}
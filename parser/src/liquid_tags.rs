@@ -0,0 +1,466 @@
+use std::collections::VecDeque;
+
+use crate::ast::Position;
+
+/// A Liquid tag of a template: `{% name markup %}`, a line of a `{% liquid %}` tag, or, when
+/// asked for, an output `{{ markup }}`, which is named `echo` like its tag form.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct Tag<'a> {
+    pub name: &'a str,
+    /// Everything after the name, up to the closing `%}`, `}}` or the end of the line.
+    pub markup: &'a str,
+    pub markup_start: usize,
+    pub start: usize,
+    pub end: usize,
+    /// For `doc` tags, the span of the `{% enddoc %}`, whose content is skipped.
+    pub closing: Option<(usize, usize)>,
+}
+
+/// Iterates over the tags of a template that Liquid would run.
+///
+/// The content of `{% raw %}`, `{% comment %}` and `{% doc %}` blocks is skipped, and the lines
+/// of `{% liquid %}` tags are yielded as tags of their own.
+pub(crate) struct LiquidTags<'a> {
+    input: &'a str,
+    cursor: usize,
+    outputs: bool,
+    lines: VecDeque<Tag<'a>>,
+}
+
+impl<'a> LiquidTags<'a> {
+    /// The tags only. `{{` in scripts and styles is common, and is no concern of callers
+    /// looking for tags.
+    pub(crate) fn new(input: &'a str) -> Self {
+        LiquidTags {
+            input,
+            cursor: 0,
+            outputs: false,
+            lines: VecDeque::new(),
+        }
+    }
+
+    /// The tags and the outputs, in source order.
+    pub(crate) fn with_outputs(input: &'a str) -> Self {
+        LiquidTags {
+            outputs: true,
+            ..LiquidTags::new(input)
+        }
+    }
+}
+
+impl<'a> Iterator for LiquidTags<'a> {
+    type Item = Tag<'a>;
+
+    fn next(&mut self) -> Option<Tag<'a>> {
+        loop {
+            if let Some(line) = self.lines.pop_front() {
+                return Some(line);
+            }
+
+            let mut tag = next_tag(self.input, self.cursor, self.outputs)?;
+            self.cursor = tag.end;
+            match tag.name {
+                "raw" => {
+                    self.cursor = closing_tag(self.input, tag.end, "raw", "endraw", false)
+                        .map_or(self.input.len(), |end| end.end)
+                }
+                // Comments nest, so the first `endcomment` may close an inner one
+                "comment" => {
+                    self.cursor = closing_tag(self.input, tag.end, "comment", "endcomment", true)
+                        .map_or(self.input.len(), |end| end.end)
+                }
+                "doc" => {
+                    let closing = closing_tag(self.input, tag.end, "doc", "enddoc", false);
+                    if let Some(end) = closing {
+                        self.cursor = end.end;
+                        tag.closing = Some((end.start, end.end));
+                    }
+                    return Some(tag);
+                }
+                "liquid" => self.lines = liquid_lines(tag.markup, tag.markup_start),
+                _ => return Some(tag),
+            }
+        }
+    }
+}
+
+/// Finds the next tag, or with `outputs` the next tag or output, at or after `from`.
+/// Returns `None` when there is none. An opener that is never closed is skipped, as Liquid
+/// renders it as text.
+pub(crate) fn next_tag(input: &str, mut from: usize, outputs: bool) -> Option<Tag<'_>> {
+    loop {
+        let rest = &input[from..];
+        let start = from
+            + match (rest.find("{%"), rest.find("{{").filter(|_| outputs)) {
+                (Some(tag), Some(output)) => tag.min(output),
+                (tag, output) => tag.or(output)?,
+            };
+        let is_output = input[start..].starts_with("{{");
+        let Some(close) = find_close(input, start + 2, if is_output { "}}" } else { "%}" }) else {
+            from = start + 2;
+            continue;
+        };
+        let inner = &input[start + 2..close];
+        let inner = inner.strip_suffix('-').unwrap_or(inner);
+
+        let trimmed = inner.trim_start_matches('-').trim_start();
+        let name_start = start + 2 + inner.len() - trimmed.len();
+        let (name, markup) = if is_output {
+            ("echo", trimmed)
+        } else {
+            split_name(trimmed)
+        };
+        return Some(Tag {
+            name,
+            markup,
+            markup_start: name_start + markup.len().abs_diff(trimmed.len()),
+            start,
+            end: close + 2,
+            closing: None,
+        });
+    }
+}
+
+/// Finds `close` at or after `from`, skipping quoted strings, so `{% echo '%}' %}` closes at
+/// the last `%}`. A quote that is never closed is taken literally. Returns `None` when another
+/// tag or output opens first.
+fn find_close(input: &str, from: usize, close: &str) -> Option<usize> {
+    let bytes = input.as_bytes();
+    let mut index = from;
+    while index < bytes.len() {
+        let rest = &bytes[index..];
+        if rest.starts_with(close.as_bytes()) {
+            return Some(index);
+        }
+        if rest.starts_with(b"{%") || rest.starts_with(b"{{") {
+            return None;
+        }
+        match bytes[index] {
+            quote @ (b'\'' | b'"') => {
+                index += match input[index + 1..].find(quote as char) {
+                    Some(end) => end + 2,
+                    None => 1,
+                };
+            }
+            _ => index += 1,
+        }
+    }
+    None
+}
+
+/// Splits `name markup`, where the name is a word. `# note` is an inline comment, whose name
+/// is not a word.
+fn split_name(text: &str) -> (&str, &str) {
+    let name_len = match text.find(|c: char| !c.is_ascii_alphanumeric() && c != '_') {
+        Some(0) if text.starts_with('#') => 1,
+        Some(len) => len,
+        None => text.len(),
+    };
+    text.split_at(name_len)
+}
+
+/// Finds the `close` tag ending a block opened just before `from`.
+/// With `nested`, every `open` tag in between needs its own `close` tag first.
+pub(crate) fn closing_tag<'a>(
+    input: &'a str,
+    mut from: usize,
+    open: &str,
+    close: &str,
+    nested: bool,
+) -> Option<Tag<'a>> {
+    let mut depth = 0;
+    while let Some(tag) = next_tag(input, from, false) {
+        from = tag.end;
+        if tag.name == close {
+            if depth == 0 {
+                return Some(tag);
+            }
+            depth -= 1;
+        } else if nested && tag.name == open {
+            depth += 1;
+        }
+    }
+    None
+}
+
+/// The tags of a `{% liquid %}` tag's markup, one per non-empty line, skipping comment and
+/// doc blocks.
+fn liquid_lines(markup: &str, markup_start: usize) -> VecDeque<Tag<'_>> {
+    let mut lines = VecDeque::new();
+    let mut skipping: Option<(&str, &str, usize)> = None;
+    let mut line_start = 0;
+
+    for line in markup.split_inclusive('\n') {
+        let start = markup_start + line_start;
+        line_start += line.len();
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let (name, rest) = split_name(trimmed);
+
+        if let Some((open, close, depth)) = skipping.as_mut() {
+            if name == *close {
+                if *depth == 0 {
+                    skipping = None;
+                } else {
+                    *depth -= 1;
+                }
+            } else if name == *open {
+                *depth += 1;
+            }
+            continue;
+        }
+        match name {
+            "comment" => skipping = Some(("comment", "endcomment", 0)),
+            "doc" => skipping = Some(("doc", "enddoc", 0)),
+            _ => {
+                let tag_start = start + line.len() - line.trim_start().len();
+                lines.push_back(Tag {
+                    name,
+                    markup: rest,
+                    markup_start: tag_start + name.len(),
+                    start: tag_start,
+                    end: tag_start + trimmed.len(),
+                    closing: None,
+                });
+            }
+        }
+    }
+
+    lines
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TokenKind {
+    String(String),
+    Number(String),
+    Range(String),
+    /// A keyword, literal word or variable path, e.g. `product.images[0]`.
+    Word(String),
+    Comma,
+    Colon,
+    /// The `|` before a filter.
+    Pipe,
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Token {
+    pub kind: TokenKind,
+    pub position: Position,
+}
+
+/// Splits tag markup into tokens, positioned in the file from the markup's `offset`.
+pub(crate) fn tokenize(markup: &str, offset: usize) -> Vec<Token> {
+    let bytes = markup.as_bytes();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let start = index;
+        let byte = bytes[index];
+        let kind = match byte {
+            _ if byte.is_ascii_whitespace() => {
+                index += 1;
+                continue;
+            }
+            b'\'' | b'"' => {
+                let end = markup[start + 1..]
+                    .find(byte as char)
+                    .map_or(markup.len(), |end| start + 1 + end);
+                index = (end + 1).min(markup.len());
+                TokenKind::String(markup[start + 1..end].to_string())
+            }
+            b'(' => {
+                index = markup[start..]
+                    .find(')')
+                    .map_or(markup.len(), |end| start + end + 1);
+                TokenKind::Range(markup[start..index].to_string())
+            }
+            b',' => {
+                index += 1;
+                TokenKind::Comma
+            }
+            b':' => {
+                index += 1;
+                TokenKind::Colon
+            }
+            b'|' => {
+                index += 1;
+                TokenKind::Pipe
+            }
+            _ if byte.is_ascii_digit()
+                || (byte == b'-' && bytes.get(index + 1).is_some_and(u8::is_ascii_digit)) =>
+            {
+                index += 1;
+                while index < bytes.len() && (bytes[index].is_ascii_digit() || bytes[index] == b'.')
+                {
+                    index += 1;
+                }
+                TokenKind::Number(markup[start..index].to_string())
+            }
+            _ if byte.is_ascii_alphabetic() || byte == b'_' => {
+                index = word_end(markup, index);
+                TokenKind::Word(markup[start..index].to_string())
+            }
+            _ => {
+                index += markup[start..].chars().next().map_or(1, char::len_utf8);
+                TokenKind::Other
+            }
+        };
+        tokens.push(Token {
+            kind,
+            position: Position::new(offset + start, offset + index, None),
+        });
+    }
+
+    tokens
+}
+
+/// The end of a variable path starting at `start`, including `[...]` lookups.
+fn word_end(markup: &str, start: usize) -> usize {
+    let bytes = markup.as_bytes();
+    let mut index = start;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'[' => {
+                index = markup[index..]
+                    .find(']')
+                    .map_or(markup.len(), |end| index + end + 1)
+            }
+            byte if byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'-' | b'.' | b'?') => {
+                index += 1
+            }
+            _ => break,
+        }
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn names(input: &str) -> Vec<(&str, &str)> {
+        LiquidTags::new(input)
+            .map(|tag| (tag.name, tag.markup))
+            .collect()
+    }
+
+    #[test]
+    fn yields_tags_with_their_markup() {
+        let input = "<p>{% render 'card', title: 'Hi' %}</p>{%- if a -%}{%endif%}";
+        let tags: Vec<Tag> = LiquidTags::new(input).collect();
+
+        assert_eq!(
+            names(input),
+            vec![
+                ("render", " 'card', title: 'Hi' "),
+                ("if", " a "),
+                ("endif", "")
+            ]
+        );
+        assert_eq!(
+            &input[tags[0].start..tags[0].end],
+            "{% render 'card', title: 'Hi' %}"
+        );
+        assert_eq!(
+            &input[tags[1].markup_start..tags[1].markup_start + tags[1].markup.len()],
+            " a "
+        );
+    }
+
+    #[test]
+    fn yields_outputs_as_echo_tags() {
+        let input = "{{- product.title | escape -}}{% if a %}{{ b }}{% endif %}";
+        let tags: Vec<Tag> = LiquidTags::with_outputs(input).collect();
+
+        assert_eq!(
+            tags.iter()
+                .map(|tag| (tag.name, tag.markup))
+                .collect::<Vec<_>>(),
+            vec![
+                ("echo", "product.title | escape "),
+                ("if", " a "),
+                ("echo", "b "),
+                ("endif", " ")
+            ]
+        );
+        assert_eq!(
+            &input[tags[0].start..tags[0].end],
+            "{{- product.title | escape -}}"
+        );
+        assert_eq!(tags[0].markup_start, 4);
+        assert_eq!(names(input), vec![("if", " a "), ("endif", " ")]);
+    }
+
+    #[test]
+    fn unclosed_outputs_do_not_hide_tags() {
+        let input = "<script>var tpl = '{{';</script>{% render 'card', title: 1 %}{{ title";
+        let tags: Vec<Tag> = LiquidTags::with_outputs(input).collect();
+
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].name, "render");
+    }
+
+    #[test]
+    fn skips_raw_comment_and_doc_content() {
+        let input = concat!(
+            "{% raw %}{% render 'a' %}{{ a }}{% endraw %}",
+            "{% comment %}{% comment %}{% endcomment %}{% render 'b' %}{% endcomment %}",
+            "{% doc %}@example {% render 'c' %}{% enddoc %}",
+            "{% # render 'd' %}",
+            "{% render 'e' %}",
+        );
+        let tags: Vec<Tag> = LiquidTags::new(input).collect();
+
+        assert_eq!(
+            names(input),
+            vec![("doc", " "), ("#", " render 'd' "), ("render", " 'e' ")]
+        );
+        let (start, end) = tags[0].closing.unwrap();
+        assert_eq!(&input[start..end], "{% enddoc %}");
+    }
+
+    #[test]
+    fn skips_unclosed_openers() {
+        let input = "<p>{% unclosed</p>{% render 'card' %}{{ title";
+        let tags: Vec<Tag> = LiquidTags::new(input).collect();
+
+        assert_eq!(tags.len(), 1);
+        assert_eq!(&input[tags[0].start..tags[0].end], "{% render 'card' %}");
+    }
+
+    #[test]
+    fn closers_inside_strings_do_not_close_tags() {
+        let input = "{% render 'card', title: '100%}' %}{% echo \"}}\" %}{% assign a = \"it's\" %}";
+
+        assert_eq!(
+            names(input),
+            vec![
+                ("render", " 'card', title: '100%}' "),
+                ("echo", " \"}}\" "),
+                ("assign", " a = \"it's\" "),
+            ]
+        );
+    }
+
+    #[test]
+    fn yields_the_lines_of_liquid_tags() {
+        let input = "{%- liquid\n  assign x = 1\n  comment\n    render 'a'\n  endcomment\n  render 'b', x: x\n-%}";
+        let tags: Vec<Tag> = LiquidTags::new(input).collect();
+
+        assert_eq!(
+            names(input),
+            vec![("assign", " x = 1"), ("render", " 'b', x: x")]
+        );
+        assert_eq!(&input[tags[1].start..tags[1].end], "render 'b', x: x");
+        assert_eq!(
+            &input[tags[1].markup_start..tags[1].markup_start + tags[1].markup.len()],
+            " 'b', x: x"
+        );
+    }
+}
@@ -0,0 +1,482 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::ast::Position;
+use crate::diagnostics::{Diagnostic, TextEdit};
+use crate::liquid_doc::LiquidDoc;
+use crate::liquid_file::{parse_liquid_file, DocBlock};
+use crate::liquid_tags::{tokenize, LiquidTags, Token, TokenKind};
+use crate::theme_index::{ThemeDiagnostic, ThemeIndex};
+
+/// Objects Shopify provides to templates, which a snippet never needs as params.
+const SHOPIFY_GLOBALS: [&str; 44] = [
+    "additional_checkout_buttons",
+    "all_products",
+    "app",
+    "articles",
+    "block",
+    "blogs",
+    "canonical_url",
+    "cart",
+    "closest",
+    "collections",
+    "content_for_additional_checkout_buttons",
+    "content_for_header",
+    "content_for_index",
+    "content_for_layout",
+    "current_page",
+    "current_tags",
+    "customer",
+    "form",
+    "forloop",
+    "handle",
+    "images",
+    "linklists",
+    "localization",
+    "metaobjects",
+    "page_description",
+    "page_image",
+    "page_title",
+    "pages",
+    "paginate",
+    "powered_by_link",
+    "predictive_search",
+    "recommendations",
+    "request",
+    "routes",
+    "scripts",
+    "search",
+    "section",
+    "settings",
+    "shop",
+    "tablerowloop",
+    "template",
+    "theme",
+    "unit_price_measurement",
+    "variant_images",
+];
+
+/// Words of the Liquid syntax that look like variables.
+const KEYWORDS: [&str; 15] = [
+    "and", "or", "contains", "in", "with", "as", "for", "by", "true", "false", "nil", "null",
+    "empty", "blank", "reversed",
+];
+
+/// Tags whose markup holds no variables.
+const IGNORED_TAGS: [&str; 7] = [
+    "#",
+    "layout",
+    "schema",
+    "style",
+    "stylesheet",
+    "javascript",
+    "doc",
+];
+
+/// Filters that only make sense on numbers.
+const ARITHMETIC_FILTERS: [&str; 7] = [
+    "plus",
+    "minus",
+    "times",
+    "divided_by",
+    "modulo",
+    "at_least",
+    "at_most",
+];
+
+/// What the way a variable is read says about its value.
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageHint {
+    /// Piped into `| default:`, so it may be missing.
+    Default,
+    /// Piped into an arithmetic filter such as `| times:`, or passed to one.
+    Arithmetic,
+    /// Read as `.size` or piped into `| size`.
+    Size,
+    /// The collection of a `for` or `tablerow` loop.
+    Iterated,
+    /// A whole operand of an `if`, `elsif` or `unless` condition.
+    Condition,
+    /// Read through a property other than `size`, e.g. `product.title`.
+    Property,
+}
+
+/// A variable read by a template that it does not define itself.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct VariableReference {
+    /// The root of the expression, e.g. `product` in `product.title`.
+    pub name: String,
+    /// Where the root is read.
+    pub position: Position,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hints: Vec<UsageHint>,
+}
+
+/// The variables a template reads before defining them with `assign`, `capture`, `for`,
+/// `tablerow`, `increment` or `decrement`, leaving out Shopify's global objects.
+///
+/// Liquid in raw, comment and doc blocks is not read.
+///
+/// ```
+/// use liquid_doc_parser::free_variables;
+///
+/// let source = "{% assign title = heading | default: product.title %}<h2>{{ title }}</h2>";
+/// let names: Vec<String> = free_variables(source).into_iter().map(|r| r.name).collect();
+/// assert_eq!(names, vec!["heading", "product"]);
+/// ```
+pub fn free_variables(source: &str) -> Vec<VariableReference> {
+    let mut locals = HashSet::new();
+    let mut references = Vec::new();
+
+    for tag in LiquidTags::with_outputs(source) {
+        if IGNORED_TAGS.contains(&tag.name) {
+            continue;
+        }
+        let tokens = tokenize(tag.markup, tag.markup_start);
+        match tag.name {
+            // The value is read before the variable is set, as in `assign x = x | default: 1`
+            "assign" => {
+                let value_start = tokens
+                    .iter()
+                    .position(|token| token.kind == TokenKind::Other)
+                    .map_or(tokens.len(), |index| index + 1);
+                collect(&tokens[value_start..], &locals, &mut references, false);
+                define(tokens.first(), &mut locals);
+            }
+            "for" | "tablerow" => {
+                let first = references.len();
+                collect(
+                    tokens.get(1..).unwrap_or_default(),
+                    &locals,
+                    &mut references,
+                    false,
+                );
+                // `for item in collection`, where the collection is the third token
+                if let Some(collection) = tokens.get(2) {
+                    for reference in &mut references[first..] {
+                        if reference.position.start == collection.position.start {
+                            reference.hints.push(UsageHint::Iterated);
+                        }
+                    }
+                }
+                define(tokens.first(), &mut locals);
+            }
+            "capture" | "increment" | "decrement" => define(tokens.first(), &mut locals),
+            "if" | "elsif" | "unless" => collect(&tokens, &locals, &mut references, true),
+            _ => collect(&tokens, &locals, &mut references, false),
+        }
+    }
+
+    references
+}
+
+/// Compares the params a template documents with the variables it reads.
+///
+/// Reports `UnusedParam` at each param that is never read, and `UndocumentedVariable` at the
+/// first read of each variable without a param, with a fix adding the `@param` to the doc.
+/// Templates without a doc block are not checked.
+///
+/// ```
+/// use liquid_doc_parser::check_param_usage;
+///
+/// let source = "{% doc %}@param {string} title{% enddoc %}<h2>{{ heading }}</h2>";
+/// let codes: Vec<String> = check_param_usage(source).into_iter().map(|d| d.code).collect();
+/// assert_eq!(codes, vec!["UnusedParam", "UndocumentedVariable"]);
+/// ```
+pub fn check_param_usage(source: &str) -> Vec<Diagnostic> {
+    let file = parse_liquid_file(source);
+    let Some(block) = file.doc() else {
+        return Vec::new();
+    };
+    let doc = LiquidDoc::from_ast(&block.ast);
+    let references = free_variables(source);
+    let mut diagnostics = Vec::new();
+
+    for param in doc.params.values() {
+        if !references
+            .iter()
+            .any(|reference| reference.name == param.name)
+        {
+            diagnostics.push(Diagnostic::warning(
+                "UnusedParam",
+                format!("'{}' is documented but never used", param.name),
+                param.node.position,
+            ));
+        }
+    }
+
+    let mut reported = HashSet::new();
+    for reference in &references {
+        if doc.param(&reference.name).is_some() || !reported.insert(&reference.name) {
+            continue;
+        }
+        let fix = add_param_edit(source, block, &doc, &reference.name);
+        diagnostics.push(
+            Diagnostic::warning(
+                "UndocumentedVariable",
+                format!("'{}' is used but not documented", reference.name),
+                reference.position,
+            )
+            .with_fix(format!("Document it with @param {}", reference.name), fix),
+        );
+    }
+
+    diagnostics
+}
+
+/// Runs [`check_param_usage`] on every documented file of the theme.
+pub fn check_theme_param_usage(index: &ThemeIndex) -> Vec<ThemeDiagnostic> {
+    index
+        .files()
+        .filter(|file| file.is_documented())
+        .flat_map(|file| {
+            check_param_usage(&file.source)
+                .into_iter()
+                .map(|diagnostic| ThemeDiagnostic {
+                    path: file.path.clone(),
+                    diagnostic,
+                })
+        })
+        .collect()
+}
+
+fn define(token: Option<&Token>, locals: &mut HashSet<String>) {
+    if let Some(Token {
+        kind: TokenKind::Word(name),
+        ..
+    }) = token
+    {
+        locals.insert(root(name).to_string());
+    }
+}
+
+/// Collects the variables read by `tokens`, skipping filter names, argument names and the
+/// names after `as`. In a `condition`, variables standing alone between `and` and `or` are
+/// hinted as conditions.
+fn collect(
+    tokens: &[Token],
+    locals: &HashSet<String>,
+    references: &mut Vec<VariableReference>,
+    condition: bool,
+) {
+    for (index, token) in tokens.iter().enumerate() {
+        let previous = index.checked_sub(1).map(|index| &tokens[index].kind);
+        let next = tokens.get(index + 1).map(|token| &token.kind);
+        match &token.kind {
+            TokenKind::Word(word) => {
+                let is_name = matches!(previous, Some(TokenKind::Pipe))
+                    || matches!(next, Some(TokenKind::Colon))
+                    || matches!(previous, Some(TokenKind::Word(keyword)) if keyword == "as");
+                if is_name {
+                    continue;
+                }
+
+                let name = root(word);
+                if !KEYWORDS.contains(&name)
+                    && !SHOPIFY_GLOBALS.contains(&name)
+                    && !locals.contains(name)
+                {
+                    let start = token.position.start;
+                    references.push(VariableReference {
+                        name: name.to_string(),
+                        position: Position::new(start, start + name.len(), None),
+                        hints: usage_hints(tokens, index, &word[name.len()..], condition),
+                    });
+                }
+                // Lookups such as `images[index]` read variables too
+                for (offset, lookup) in lookups(word) {
+                    collect(
+                        &tokenize(lookup, token.position.start + offset),
+                        locals,
+                        references,
+                        false,
+                    );
+                }
+            }
+            TokenKind::Range(range) => {
+                let inner = range.trim_start_matches('(').trim_end_matches(')');
+                let inner = inner.replace("..", "  ");
+                collect(
+                    &tokenize(&inner, token.position.start + 1),
+                    locals,
+                    references,
+                    false,
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The hints given by the variable at `index`, read through `path`, e.g. `.size`.
+fn usage_hints(tokens: &[Token], index: usize, path: &str, condition: bool) -> Vec<UsageHint> {
+    let kind = |index: Option<usize>| index.and_then(|index| tokens.get(index)).map(|t| &t.kind);
+    let is_word = |index: Option<usize>, words: &[&str]| matches!(kind(index), Some(TokenKind::Word(word)) if words.contains(&word.as_str()));
+    let mut hints = Vec::new();
+
+    if path == ".size" {
+        hints.push(UsageHint::Size);
+    } else if path.starts_with('.') {
+        hints.push(UsageHint::Property);
+    }
+
+    if matches!(kind(Some(index + 1)), Some(TokenKind::Pipe)) {
+        match kind(Some(index + 2)) {
+            Some(TokenKind::Word(filter)) if filter == "default" => hints.push(UsageHint::Default),
+            Some(TokenKind::Word(filter)) if filter == "size" => hints.push(UsageHint::Size),
+            Some(TokenKind::Word(filter)) if ARITHMETIC_FILTERS.contains(&filter.as_str()) => {
+                hints.push(UsageHint::Arithmetic)
+            }
+            _ => {}
+        }
+    }
+    // The argument of `| times: count`
+    if matches!(kind(index.checked_sub(1)), Some(TokenKind::Colon))
+        && is_word(index.checked_sub(2), &ARITHMETIC_FILTERS)
+        && matches!(kind(index.checked_sub(3)), Some(TokenKind::Pipe))
+        && !hints.contains(&UsageHint::Arithmetic)
+    {
+        hints.push(UsageHint::Arithmetic);
+    }
+
+    let previous = index.checked_sub(1);
+    let stands_alone = (previous.is_none() || is_word(previous, &["and", "or"]))
+        && (index + 1 == tokens.len() || is_word(Some(index + 1), &["and", "or"]));
+    if condition && stands_alone && path.is_empty() {
+        hints.push(UsageHint::Condition);
+    }
+
+    hints
+}
+
+/// The variable a path starts from, e.g. `product` in `product.images[0]`.
+fn root(path: &str) -> &str {
+    path.split(['.', '[']).next().unwrap_or(path)
+}
+
+/// The contents of the `[...]` lookups of a path, with their byte offsets in it.
+fn lookups(path: &str) -> Vec<(usize, &str)> {
+    let mut lookups = Vec::new();
+    let mut from = 0;
+    while let Some(open) = path[from..].find('[') {
+        let start = from + open + 1;
+        let end = path[start..]
+            .find(']')
+            .map_or(path.len(), |end| start + end);
+        lookups.push((start, &path[start..end]));
+        from = end;
+    }
+    lookups
+}
+
+/// Inserts `@param name` after the last param of the doc, or at the end of its content,
+/// indented like the doc's other lines.
+fn add_param_edit(source: &str, block: &DocBlock, doc: &LiquidDoc, name: &str) -> TextEdit {
+    let body = &source[block.body_position.start..block.body_position.end];
+    let insert_at = match doc.params.values().last() {
+        Some(param) => param.node.position.end,
+        None => block.body_position.start + body.trim_end().len(),
+    };
+
+    let line_start = source[..insert_at].rfind('\n').map_or(0, |index| index + 1);
+    let line = &source[line_start.max(block.body_position.start)..insert_at];
+    let indent = &line[..line.len() - line.trim_start().len()];
+    TextEdit {
+        position: Position::new(insert_at, insert_at, None),
+        new_text: format!("\n{}@param {}", indent, name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn names(source: &str) -> Vec<String> {
+        free_variables(source)
+            .into_iter()
+            .map(|reference| reference.name)
+            .collect()
+    }
+
+    #[test]
+    fn ignores_locals_globals_and_syntax() {
+        let source = concat!(
+            "{% assign heading = heading | default: 'Hi' | append: suffix %}",
+            "{% capture label %}{{ heading }}{% endcapture %}",
+            "{% for image in product.images limit: max %}{{ image.src | image_url: width: 300 }}{{ forloop.index }}{% endfor %}",
+            "{% if settings.show and label != blank %}{{ label }}{% endif %}",
+            "{% render 'card' with item as card, title: heading %}",
+            "{% comment %}{{ hidden }}{% endcomment %}",
+        );
+
+        assert_eq!(
+            names(source),
+            vec!["heading", "suffix", "product", "max", "item"]
+        );
+    }
+
+    #[test]
+    fn unclosed_outputs_are_not_read() {
+        let source = "<script>var tpl = '{{';</script>{% render 'card', title: heading %}";
+
+        assert_eq!(names(source), vec!["heading"]);
+    }
+
+    #[test]
+    fn reads_liquid_tags_ranges_and_lookups() {
+        let source = "{% liquid\n  for i in (1..count)\n    echo photos[index].alt\n  endfor\n%}";
+        let references = free_variables(source);
+
+        assert_eq!(
+            references
+                .iter()
+                .map(|reference| (
+                    reference.name.as_str(),
+                    &source[reference.position.start..reference.position.end]
+                ))
+                .collect::<Vec<_>>(),
+            vec![("count", "count"), ("photos", "photos"), ("index", "index")]
+        );
+    }
+
+    #[test]
+    fn reports_unused_and_undocumented_variables() {
+        let source = "{% doc %}\n  Renders a card\n\n  @param {string} title\n  @param {number} width\n{% enddoc %}\n<h2>{{ heading }}</h2>\n{{ title }}{{ heading | upcase }}";
+        let diagnostics = check_param_usage(source);
+
+        let found: Vec<(&str, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.code.as_str(), &source[d.position.start..d.position.end]))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("UnusedParam", "@param {number} width"),
+                ("UndocumentedVariable", "heading"),
+            ]
+        );
+
+        let fix = diagnostics[1].fix.as_ref().unwrap();
+        let mut fixed = source.to_string();
+        fixed.replace_range(fix.position.start..fix.position.end, &fix.new_text);
+        assert!(fixed.contains("  @param {number} width\n  @param heading\n{% enddoc %}"));
+        assert_eq!(check_param_usage(&fixed).len(), 1);
+    }
+
+    #[test]
+    fn adds_the_first_param_after_the_description() {
+        let source = "{% doc %}\n  Renders a card\n{% enddoc %}\n{{ title }}";
+        let fix = check_param_usage(source)[0].fix.clone().unwrap();
+
+        assert_eq!(fix.new_text, "\n  @param title");
+        assert_eq!(&source[..fix.position.start], "{% doc %}\n  Renders a card");
+    }
+
+    #[test]
+    fn undocumented_templates_are_not_checked() {
+        assert_eq!(check_param_usage("{{ title }}"), vec![]);
+    }
+}
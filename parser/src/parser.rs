@@ -1,71 +1,94 @@
-use crate::ast::{LiquidDocParamNode, *};
-use pest::Parser;
+use crate::ast::*;
+use crate::events::{node_from_pair, parse_doc_events, TreeBuilder};
 use pest_derive::Parser;
 
 #[derive(Parser)]
 #[grammar = "liquid.pest"]
 pub struct LiquidParser;
 
-pub fn visit(
-    ast: &mut LiquidAST,
-    pair: pest::iterators::Pair<Rule>,
-    position_offset: Option<usize>,
-) {
+pub fn visit(ast: &mut LiquidAST, pair: pest::iterators::Pair<Rule>, options: &ParseOptions) {
     match pair.as_rule() {
         Rule::LiquidDocNode | Rule::Document => {
             for inner_pair in pair.into_inner() {
-                visit(ast, inner_pair, position_offset);
+                visit(ast, inner_pair, options);
             }
         }
-        Rule::ImplicitDescription => {
-            let node = LiquidDocDescriptionNode::implicit(&pair, position_offset);
-            if !node.value().is_empty() {
-                // If the description starts with '@', it's a fallback, treat it as a text node
-                if node.value().starts_with("@") {
-                    ast.add_node(LiquidNode::TextNode(TextNode::new(
-                        node.value().to_string(),
-                        node.position,
-                        node.source.clone(),
-                    )));
-                } else {
-                    ast.add_node(LiquidNode::LiquidDocDescriptionNode(node));
-                }
+        _ => {
+            if let Some(node) = node_from_pair(pair, options) {
+                ast.add_node(node);
             }
         }
-        Rule::ParamNode => {
-            let node = LiquidDocParamNode::from_pair(&pair, position_offset);
-            ast.add_node(LiquidNode::LiquidDocParamNode(node));
-        }
-        Rule::ExampleNode => {
-            let node = LiquidDocExampleNode::from_pair(&pair, position_offset);
-            ast.add_node(LiquidNode::LiquidDocExampleNode(node));
-        }
-        Rule::DescriptionNode => {
-            let node = LiquidDocDescriptionNode::explicit(&pair, position_offset);
-            ast.add_node(LiquidNode::LiquidDocDescriptionNode(node));
-        }
-        Rule::PromptNode | Rule::FallbackNode | Rule::TextNode => {
-            let text_node = TextNode::from_pair(&pair, position_offset);
-            if !text_node.is_empty() {
-                ast.add_node(LiquidNode::TextNode(text_node));
-            }
-        }
-        _ => unimplemented!("Handle rule: {:?}", pair.as_rule()),
     }
 }
 
-pub fn parse_liquid_string(
-    input: &str,
-    position_offset: Option<usize>,
-) -> Option<LiquidAST> {
-    let text = LiquidParser::parse(Rule::Document, input)
-        .map_err(|e| println!("Parsing error: {}", e))
-        .ok()?;
+/// Options controlling how a LiquidDoc string is turned into a `LiquidAST`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Added to every position, e.g. the offset of the `{% doc %}` body in the template.
+    pub position_offset: Option<usize>,
+    /// Rewrites `\r\n` and lone `\r` line endings to `\n` in node values.
+    /// Positions and `source` fields keep pointing at the original bytes.
+    pub normalize_line_endings: bool,
+    /// Emits unknown tags (e.g. `@unsupported`) as plain `TextNode`s instead of
+    /// `LiquidDocUnknownTagNode`s, matching the shape liquid-html-parser produces.
+    pub fallback_text_nodes: bool,
+}
+
+impl ParseOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_position_offset(mut self, position_offset: Option<usize>) -> Self {
+        self.position_offset = position_offset;
+        self
+    }
+
+    pub fn with_normalized_line_endings(mut self, normalize_line_endings: bool) -> Self {
+        self.normalize_line_endings = normalize_line_endings;
+        self
+    }
+
+    pub fn with_fallback_text_nodes(mut self, fallback_text_nodes: bool) -> Self {
+        self.fallback_text_nodes = fallback_text_nodes;
+        self
+    }
+}
+
+pub fn parse_liquid_string(input: &str, position_offset: Option<usize>) -> Option<LiquidAST> {
+    parse_liquid_string_with_options(
+        input,
+        &ParseOptions::new().with_position_offset(position_offset),
+    )
+}
+
+pub fn parse_liquid_string_with_options(input: &str, options: &ParseOptions) -> Option<LiquidAST> {
+    let nodes = parse_liquid_nodes(input, options)?;
 
-    let mut ast = LiquidAST::new();
-    for pair in text {
-        visit(&mut ast, pair, position_offset);
+    let mut ast = LiquidAST::for_source(input.len(), options.position_offset);
+    for node in nodes {
+        ast.add_node(node);
     }
 
     Some(ast)
-}
\ No newline at end of file
+}
+
+/// Parses `input` like [`parse_liquid_string_with_options`], but builds each node only when
+/// the iterator reaches it, so callers can handle nodes without holding the whole AST.
+/// Returns `None` when `input` does not parse, before any node is built.
+pub fn parse_liquid_nodes<'a>(
+    input: &'a str,
+    options: &'a ParseOptions,
+) -> Option<impl Iterator<Item = LiquidNode> + 'a> {
+    let mut events = parse_doc_events(input, options)?;
+    let builder = TreeBuilder::new(input, options.position_offset);
+
+    let nodes = std::iter::from_fn(move || builder.next_node(&mut events)).map(move |mut node| {
+        if options.normalize_line_endings {
+            node.normalize_line_endings();
+        }
+        node
+    });
+
+    Some(nodes)
+}
@@ -0,0 +1,522 @@
+use serde::Serialize;
+
+use crate::ast::Position;
+use crate::diagnostics::Diagnostic;
+use crate::liquid_doc::{LiquidDoc, ParamType};
+use crate::liquid_tags::{tokenize, LiquidTags, Token, TokenKind};
+use crate::theme_index::{ThemeDiagnostic, ThemeFile, ThemeIndex};
+
+/// A value passed in a tag's markup.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", content = "value", rename_all = "lowercase")]
+pub enum ArgumentValue {
+    /// The content between the quotes.
+    String(String),
+    Number(String),
+    Boolean(bool),
+    Nil,
+    /// `(1..5)`
+    Range(String),
+    /// Any other expression, e.g. `product.title`.
+    Variable(String),
+}
+
+impl ArgumentValue {
+    /// What a literal is called in messages, or `None` for values only known at render time.
+    fn literal_kind(&self) -> Option<&'static str> {
+        match self {
+            ArgumentValue::String(_) => Some("string"),
+            ArgumentValue::Number(_) => Some("number"),
+            ArgumentValue::Boolean(_) => Some("boolean"),
+            ArgumentValue::Range(_) => Some("range"),
+            ArgumentValue::Nil | ArgumentValue::Variable(_) => None,
+        }
+    }
+}
+
+/// A `name: value` argument.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Argument {
+    pub name: String,
+    pub value: ArgumentValue,
+    /// From the start of the name to the end of the value.
+    pub position: Position,
+    #[serde(rename = "namePosition")]
+    pub name_position: Position,
+    #[serde(rename = "valuePosition")]
+    pub value_position: Position,
+}
+
+/// The snippet a render names: a string literal, or an expression only known at render time.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", content = "value", rename_all = "lowercase")]
+pub enum RenderTarget {
+    Static(String),
+    Dynamic(String),
+}
+
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BindingKind {
+    With,
+    For,
+}
+
+/// The `with expression as name` or `for expression as name` part of a render.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct RenderBinding {
+    pub kind: BindingKind,
+    pub expression: ArgumentValue,
+    pub position: Position,
+    /// The variable the snippet sees. Without `as`, it is named after the snippet.
+    pub alias: Option<String>,
+}
+
+/// A `{% render %}` tag of a template.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct RenderCall {
+    /// The whole tag, or the line of a `{% liquid %}` tag.
+    pub position: Position,
+    pub target: RenderTarget,
+    #[serde(rename = "targetPosition")]
+    pub target_position: Position,
+    pub binding: Option<RenderBinding>,
+    pub arguments: Vec<Argument>,
+}
+
+impl RenderCall {
+    /// The variable a binding sets in the snippet, if any.
+    pub fn bound_variable(&self) -> Option<&str> {
+        let binding = self.binding.as_ref()?;
+        match (&binding.alias, &self.target) {
+            (Some(alias), _) => Some(alias),
+            (None, RenderTarget::Static(name)) => Some(name),
+            (None, RenderTarget::Dynamic(_)) => None,
+        }
+    }
+}
+
+/// Finds the `{% render %}` tags of a template, outside raw, comment and doc blocks and
+/// including the lines of `{% liquid %}` tags.
+///
+/// ```
+/// use liquid_doc_parser::{parse_render_calls, RenderTarget};
+///
+/// let calls = parse_render_calls("{% render 'card', title: product.title %}");
+/// assert_eq!(calls[0].target, RenderTarget::Static("card".to_string()));
+/// assert_eq!(calls[0].arguments[0].name, "title");
+/// ```
+pub fn parse_render_calls(source: &str) -> Vec<RenderCall> {
+    parse_calls(source, &["render"])
+        .into_iter()
+        .map(|(_, call)| call)
+        .collect()
+}
+
+/// Reads every tag named in `names` as a render, with the name of its tag. `{% include %}` and
+/// `{% section %}` share the syntax of `{% render %}`.
+pub(crate) fn parse_calls<'a>(source: &'a str, names: &[&str]) -> Vec<(&'a str, RenderCall)> {
+    LiquidTags::new(source)
+        .filter(|tag| names.contains(&tag.name))
+        .filter_map(|tag| {
+            let tokens = tokenize(tag.markup, tag.markup_start);
+            let (target, rest) = tokens.split_first()?;
+            let target_position = target.position;
+            let target = match &target.kind {
+                TokenKind::String(name) => RenderTarget::Static(name.clone()),
+                TokenKind::Word(expression) => RenderTarget::Dynamic(expression.clone()),
+                _ => return None,
+            };
+
+            let (binding, rest) = parse_binding(rest);
+            let call = RenderCall {
+                position: Position::new(tag.start, tag.end, None),
+                target,
+                target_position,
+                binding,
+                arguments: parse_arguments(rest),
+            };
+            Some((tag.name, call))
+        })
+        .collect()
+}
+
+/// Checks every render of the theme against the `@param`s of the snippet it renders.
+pub fn check_render_calls(index: &ThemeIndex) -> Vec<ThemeDiagnostic> {
+    index
+        .files()
+        .flat_map(|file| {
+            check_file_render_calls(index, file)
+                .into_iter()
+                .map(|diagnostic| ThemeDiagnostic {
+                    path: file.path.clone(),
+                    diagnostic,
+                })
+        })
+        .collect()
+}
+
+/// Checks the renders of one file, e.g. the one open in an editor.
+///
+/// Renders of undocumented or missing snippets, and of dynamic names, are not checked.
+pub fn check_file_render_calls(index: &ThemeIndex, file: &ThemeFile) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for call in parse_render_calls(&file.source) {
+        let RenderTarget::Static(name) = &call.target else {
+            continue;
+        };
+        let Some(doc) = index
+            .snippet(name, file)
+            .and_then(|snippet| snippet.doc.as_ref())
+        else {
+            continue;
+        };
+
+        let bound = call.bound_variable().into_iter().collect::<Vec<_>>();
+        diagnostics.extend(check_arguments(
+            &format!("snippet '{}'", name),
+            doc,
+            &call.arguments,
+            &bound,
+            call.position,
+        ));
+    }
+    diagnostics
+}
+
+/// Checks `arguments`, plus the variables `bound` another way, against the params of `doc`.
+/// Missing params are reported at `position`.
+pub(crate) fn check_arguments(
+    target: &str,
+    doc: &LiquidDoc,
+    arguments: &[Argument],
+    bound: &[&str],
+    position: Position,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for argument in arguments {
+        let Some(param) = doc.param(&argument.name) else {
+            diagnostics.push(Diagnostic::warning(
+                "UnknownArgument",
+                format!(
+                    "Unknown argument '{}': {} has no such @param",
+                    argument.name, target
+                ),
+                argument.position,
+            ));
+            continue;
+        };
+
+        let Some(param_type) = &param.param_type else {
+            continue;
+        };
+        if let Some(kind) = literal_mismatch(param_type, &argument.value) {
+            diagnostics.push(Diagnostic::warning(
+                "ArgumentTypeMismatch",
+                format!(
+                    "Argument '{}' is a {}, but {} declares it as {}",
+                    argument.name, kind, target, param_type
+                ),
+                argument.value_position,
+            ));
+        }
+    }
+
+    for param in doc.required_params() {
+        let passed = arguments.iter().any(|argument| argument.name == param.name)
+            || bound.contains(&param.name.as_str());
+        if !passed {
+            diagnostics.push(Diagnostic::error(
+                "MissingRequiredParam",
+                format!("Missing required argument '{}' for {}", param.name, target),
+                position,
+            ));
+        }
+    }
+
+    diagnostics
+}
+
+/// The kind of a literal that can never be a `param_type`. Values only known at render time,
+/// and types other than the primitives and arrays, are never a mismatch.
+fn literal_mismatch(param_type: &ParamType, value: &ArgumentValue) -> Option<&'static str> {
+    let kind = value.literal_kind()?;
+    let matches = match param_type {
+        ParamType::String => kind == "string",
+        ParamType::Number => kind == "number",
+        ParamType::Boolean => kind == "boolean",
+        ParamType::Array(_) => kind == "range",
+        ParamType::Object | ParamType::Named(_) => true,
+    };
+    (!matches).then_some(kind)
+}
+
+fn parse_binding(tokens: &[Token]) -> (Option<RenderBinding>, &[Token]) {
+    let kind = match tokens.first().map(|token| &token.kind) {
+        Some(TokenKind::Word(word)) if word == "with" => BindingKind::With,
+        Some(TokenKind::Word(word)) if word == "for" => BindingKind::For,
+        _ => return (None, tokens),
+    };
+    let Some(expression) = tokens.get(1).and_then(Token::value) else {
+        return (None, tokens);
+    };
+
+    let mut binding = RenderBinding {
+        kind,
+        expression,
+        position: Position::new(tokens[0].position.start, tokens[1].position.end, None),
+        alias: None,
+    };
+    let mut rest = &tokens[2..];
+    if let [as_token, alias, after @ ..] = rest {
+        if let (TokenKind::Word(keyword), TokenKind::Word(name)) = (&as_token.kind, &alias.kind) {
+            if keyword == "as" {
+                binding.alias = Some(name.clone());
+                binding.position.end = alias.position.end;
+                rest = after;
+            }
+        }
+    }
+    (Some(binding), rest)
+}
+
+/// Reads `name: value` pairs, skipping commas and anything it does not understand.
+pub(crate) fn parse_arguments(tokens: &[Token]) -> Vec<Argument> {
+    let mut arguments = Vec::new();
+    let mut index = 0;
+    while index < tokens.len() {
+        if let [name, colon, value, ..] = &tokens[index..] {
+            if let (TokenKind::Word(key), TokenKind::Colon, Some(argument_value)) =
+                (&name.kind, &colon.kind, value.value())
+            {
+                arguments.push(Argument {
+                    name: key.clone(),
+                    value: argument_value,
+                    position: Position::new(name.position.start, value.position.end, None),
+                    name_position: name.position,
+                    value_position: value.position,
+                });
+                index += 3;
+                continue;
+            }
+        }
+        index += 1;
+    }
+    arguments
+}
+
+impl Token {
+    pub(crate) fn value(&self) -> Option<ArgumentValue> {
+        Some(match &self.kind {
+            TokenKind::String(value) => ArgumentValue::String(value.clone()),
+            TokenKind::Number(value) => ArgumentValue::Number(value.clone()),
+            TokenKind::Range(value) => ArgumentValue::Range(value.clone()),
+            TokenKind::Word(word) => match word.as_str() {
+                "true" => ArgumentValue::Boolean(true),
+                "false" => ArgumentValue::Boolean(false),
+                "nil" | "null" => ArgumentValue::Nil,
+                _ => ArgumentValue::Variable(word.clone()),
+            },
+            TokenKind::Comma | TokenKind::Colon | TokenKind::Pipe | TokenKind::Other => {
+                return None
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const CARD: &str = "{% doc %}\n  @param {string} title\n  @param {number} [width]\n  @param {boolean} [lazy]\n  @param {product} product\n{% enddoc %}";
+
+    fn index(template: &str) -> ThemeIndex {
+        let mut index = ThemeIndex::new("theme");
+        index.update_file("snippets/card.liquid", CARD);
+        index.update_file("sections/main.liquid", template);
+        index
+    }
+
+    fn codes_at(template: &str) -> Vec<(String, &str)> {
+        let index = index(template);
+        check_file_render_calls(&index, index.section("main").unwrap())
+            .into_iter()
+            .map(|diagnostic| {
+                let position = diagnostic.position;
+                (diagnostic.code, &template[position.start..position.end])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_render_markup() {
+        let source = "{% render 'card' with product as item, title: 'Hi', width: -3.5, lazy: true, range: (1..3), x: nil %}";
+        let call = &parse_render_calls(source)[0];
+
+        assert_eq!(
+            &source[call.target_position.start..call.target_position.end],
+            "'card'"
+        );
+        let binding = call.binding.as_ref().unwrap();
+        assert_eq!(binding.kind, BindingKind::With);
+        assert_eq!(
+            binding.expression,
+            ArgumentValue::Variable("product".to_string())
+        );
+        assert_eq!(call.bound_variable(), Some("item"));
+        assert_eq!(
+            call.arguments
+                .iter()
+                .map(|argument| (argument.name.as_str(), argument.value.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("title", ArgumentValue::String("Hi".to_string())),
+                ("width", ArgumentValue::Number("-3.5".to_string())),
+                ("lazy", ArgumentValue::Boolean(true)),
+                ("range", ArgumentValue::Range("(1..3)".to_string())),
+                ("x", ArgumentValue::Nil),
+            ]
+        );
+        let width = &call.arguments[1];
+        assert_eq!(
+            &source[width.position.start..width.position.end],
+            "width: -3.5"
+        );
+    }
+
+    #[test]
+    fn for_without_alias_binds_the_snippet_name() {
+        let call = &parse_render_calls("{%- render 'card' for products -%}")[0];
+
+        assert_eq!(call.binding.as_ref().unwrap().kind, BindingKind::For);
+        assert_eq!(call.bound_variable(), Some("card"));
+    }
+
+    #[test]
+    fn braces_in_scripts_do_not_hide_calls() {
+        let calls =
+            parse_render_calls("<script>var tpl = '{{';</script>{% render 'card', title: 1 %}");
+
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].target, RenderTarget::Static("card".to_string()));
+        assert_eq!(calls[0].arguments[0].name, "title");
+    }
+
+    #[test]
+    fn dynamic_targets_are_kept() {
+        let calls = parse_render_calls("{% render block.settings.snippet %}");
+
+        assert_eq!(
+            calls[0].target,
+            RenderTarget::Dynamic("block.settings.snippet".to_string())
+        );
+    }
+
+    #[test]
+    fn bindings_count_as_arguments() {
+        assert_eq!(
+            codes_at("{% render 'card', title: product.title, product: product, width: 3 %}"),
+            vec![]
+        );
+        assert_eq!(
+            codes_at("{% render 'card' for products as product, title: nil %}{% render 'card' with product, title: 'Hi' %}"),
+            vec![("MissingRequiredParam".to_string(), "{% render 'card' with product, title: 'Hi' %}")]
+        );
+    }
+
+    #[test]
+    fn reports_missing_unknown_and_mismatched_arguments() {
+        let template =
+            "<div>\n{% liquid\n  render 'card', titel: 'Hi', product: product, width: '3', lazy: 1\n%}</div>";
+
+        assert_eq!(
+            codes_at(template),
+            vec![
+                ("UnknownArgument".to_string(), "titel: 'Hi'"),
+                ("ArgumentTypeMismatch".to_string(), "'3'"),
+                ("ArgumentTypeMismatch".to_string(), "1"),
+                (
+                    "MissingRequiredParam".to_string(),
+                    "render 'card', titel: 'Hi', product: product, width: '3', lazy: 1"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_undocumented_missing_and_dynamic_snippets() {
+        let mut index = index("{% render 'plain', a: 1 %}{% render 'missing' %}{% render name %}");
+        index.update_file("snippets/plain.liquid", "<p></p>");
+
+        assert_eq!(check_render_calls(&index), vec![]);
+    }
+
+    #[test]
+    fn extensions_render_their_own_snippets() {
+        let mut index = ThemeIndex::new("theme");
+        index.update_file(
+            "snippets/rating.liquid",
+            "{% doc %}@param {string} label{% enddoc %}",
+        );
+        index.update_file(
+            "extensions/reviews/snippets/rating.liquid",
+            "{% doc %}@param {number} score{% enddoc %}",
+        );
+        index.update_file(
+            "extensions/reviews/blocks/stars.liquid",
+            "{% render 'rating', score: 3 %}",
+        );
+        index.update_file("sections/main.liquid", "{% render 'rating', score: 3 %}");
+
+        let codes = |path: &str| -> Vec<String> {
+            check_file_render_calls(&index, index.file(path).unwrap())
+                .into_iter()
+                .map(|diagnostic| diagnostic.code)
+                .collect()
+        };
+        assert_eq!(
+            codes("extensions/reviews/blocks/stars.liquid"),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            codes("sections/main.liquid"),
+            vec![
+                "UnknownArgument".to_string(),
+                "MissingRequiredParam".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_every_file_of_the_theme() {
+        let mut index = index("{% render 'card' %}");
+        index.update_file(
+            "snippets/other.liquid",
+            "{% render 'card', title: 1, product: p %}",
+        );
+
+        let diagnostics: Vec<(String, String)> = check_render_calls(&index)
+            .into_iter()
+            .map(|d| (d.path.display().to_string(), d.diagnostic.code))
+            .collect();
+        assert_eq!(
+            diagnostics,
+            vec![
+                (
+                    "sections/main.liquid".to_string(),
+                    "MissingRequiredParam".to_string()
+                ),
+                (
+                    "sections/main.liquid".to_string(),
+                    "MissingRequiredParam".to_string()
+                ),
+                (
+                    "snippets/other.liquid".to_string(),
+                    "ArgumentTypeMismatch".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,868 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::ast::Position;
+use crate::content_for_calls::parse_content_for_calls;
+use crate::liquid_tags::LiquidTags;
+use crate::render_calls::{parse_calls, Argument, RenderBinding, RenderCall, RenderTarget};
+use crate::theme_index::{ThemeFile, ThemeFileKind, ThemeIndex};
+
+/// How a file renders another.
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum EdgeKind {
+    Render,
+    Include,
+    /// A `{% section %}` tag, or a section of a JSON template or section group.
+    Section,
+    /// A `{% sections %}` tag, rendering a section group.
+    Sections,
+    /// A static `{% content_for 'block' %}` tag.
+    ContentFor,
+    /// A block type named in a `{% schema %}`, or in a JSON template or section group.
+    Block,
+}
+
+impl EdgeKind {
+    const TAGS: [&'static str; 4] = ["render", "include", "section", "sections"];
+
+    fn from_tag(tag: &str) -> Option<Self> {
+        match tag {
+            "render" => Some(EdgeKind::Render),
+            "include" => Some(EdgeKind::Include),
+            "section" => Some(EdgeKind::Section),
+            "sections" => Some(EdgeKind::Sections),
+            _ => None,
+        }
+    }
+
+    fn tag(self) -> &'static str {
+        match self {
+            EdgeKind::Render => "render",
+            EdgeKind::Include => "include",
+            EdgeKind::Section => "section",
+            EdgeKind::Sections => "sections",
+            EdgeKind::ContentFor => "content_for",
+            EdgeKind::Block => "block",
+        }
+    }
+
+    /// The kind of file the tag names.
+    fn target_kind(self) -> ThemeFileKind {
+        match self {
+            EdgeKind::Render | EdgeKind::Include => ThemeFileKind::Snippet,
+            EdgeKind::Section => ThemeFileKind::Section,
+            EdgeKind::Sections => ThemeFileKind::SectionGroup,
+            EdgeKind::ContentFor | EdgeKind::Block => ThemeFileKind::Block,
+        }
+    }
+}
+
+/// A file of the theme.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct GraphNode {
+    /// Relative to the theme root, which identifies the node.
+    pub path: PathBuf,
+    pub kind: ThemeFileKind,
+    pub name: String,
+}
+
+/// A tag of one file rendering another, or a section or block type it names in JSON.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct GraphEdge {
+    pub from: PathBuf,
+    /// The file rendered, or `None` when the target is dynamic or names no indexed file.
+    pub to: Option<PathBuf>,
+    pub kind: EdgeKind,
+    pub target: RenderTarget,
+    /// The tag, in `from`, or `None` for a type named in a `{% schema %}` or JSON file.
+    pub position: Option<Position>,
+    pub binding: Option<RenderBinding>,
+    pub arguments: Vec<Argument>,
+}
+
+impl GraphEdge {
+    fn new(from: &ThemeFile, kind: EdgeKind, call: RenderCall, index: &ThemeIndex) -> Self {
+        let mut edge = GraphEdge::named(from, kind, call.target, index);
+        edge.position = Some(call.position);
+        edge.binding = call.binding;
+        edge.arguments = call.arguments;
+        edge
+    }
+
+    /// An edge without a tag, or before the tag's details are filled in. The target is looked
+    /// up as `from` names it, within its theme app extension if it is in one.
+    fn named(from: &ThemeFile, kind: EdgeKind, target: RenderTarget, index: &ThemeIndex) -> Self {
+        let to = match &target {
+            RenderTarget::Static(name) => index
+                .get(kind.target_kind(), name, from)
+                .map(|file| file.path.clone()),
+            RenderTarget::Dynamic(_) => None,
+        };
+        GraphEdge {
+            from: from.path.clone(),
+            to,
+            kind,
+            target,
+            position: None,
+            binding: None,
+            arguments: Vec::new(),
+        }
+    }
+
+    /// Whether the target is an expression only known at render time.
+    pub fn is_dynamic(&self) -> bool {
+        matches!(self.target, RenderTarget::Dynamic(_))
+    }
+
+    /// The variables the rendered file receives: the bound variable, then the arguments.
+    pub fn passed_names(&self) -> Vec<&str> {
+        let bound =
+            self.binding
+                .as_ref()
+                .and_then(|binding| match (&binding.alias, &self.target) {
+                    (Some(alias), _) => Some(alias.as_str()),
+                    (None, RenderTarget::Static(name)) => Some(name.as_str()),
+                    (None, RenderTarget::Dynamic(_)) => None,
+                });
+        bound
+            .into_iter()
+            .chain(self.arguments.iter().map(|argument| argument.name.as_str()))
+            .collect()
+    }
+
+    /// e.g. `render: title, product`
+    fn label(&self) -> String {
+        let names = self.passed_names();
+        if names.is_empty() {
+            self.kind.tag().to_string()
+        } else {
+            format!("{}: {}", self.kind.tag(), names.join(", "))
+        }
+    }
+
+    /// Names the node drawn for a target that is not an indexed file.
+    fn unresolved_label(&self) -> String {
+        match &self.target {
+            RenderTarget::Static(name) => format!("missing {} '{}'", self.kind.tag(), name),
+            RenderTarget::Dynamic(expression) => format!("dynamic {}", expression),
+        }
+    }
+}
+
+/// Which files of a theme render which, for impact analysis before changing a snippet.
+///
+/// Nodes are the indexed files, ordered by path. Edges are their `{% render %}`,
+/// `{% include %}`, `{% section %}`, `{% sections %}` and static `{% content_for 'block' %}`
+/// tags in source order, with the arguments they pass, followed by the block types of their
+/// `{% schema %}`. JSON templates and section groups have an edge per section and block type.
+///
+/// ```
+/// use liquid_doc_parser::{RenderGraph, ThemeIndex};
+///
+/// let mut index = ThemeIndex::new("theme");
+/// index.update_file("sections/main.liquid", "{% render 'card', title: 'Hi' %}");
+/// index.update_file("snippets/card.liquid", "<h2>{{ title }}</h2>");
+///
+/// let graph = RenderGraph::build(&index);
+/// let callers: Vec<_> = graph.edges_to("snippets/card.liquid").collect();
+/// assert_eq!(callers[0].passed_names(), vec!["title"]);
+/// assert!(graph.cycles.is_empty());
+/// ```
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct RenderGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    /// Each set of files that render each other, as a path from the file first by path back to
+    /// itself, e.g. `[a, b, a]`.
+    pub cycles: Vec<Vec<PathBuf>>,
+}
+
+impl RenderGraph {
+    pub fn build(index: &ThemeIndex) -> Self {
+        let mut graph = RenderGraph::default();
+        for file in index.files() {
+            graph.nodes.push(GraphNode {
+                path: file.path.clone(),
+                kind: file.kind,
+                name: file.name.clone(),
+            });
+            graph.edges.extend(file_edges(file, index));
+        }
+        graph.cycles = find_cycles(&graph.nodes, &graph.edges);
+        graph
+    }
+
+    pub fn edges_from(&self, path: impl AsRef<Path>) -> impl Iterator<Item = &GraphEdge> {
+        let path = path.as_ref().to_path_buf();
+        self.edges.iter().filter(move |edge| edge.from == path)
+    }
+
+    /// The tags rendering `path`, with the arguments each passes.
+    pub fn edges_to(&self, path: impl AsRef<Path>) -> impl Iterator<Item = &GraphEdge> {
+        let path = path.as_ref().to_path_buf();
+        self.edges
+            .iter()
+            .filter(move |edge| edge.to.as_ref() == Some(&path))
+    }
+
+    /// Every file that renders `path`, directly or through other files.
+    pub fn dependents(&self, path: impl AsRef<Path>) -> BTreeSet<&Path> {
+        let mut dependents = BTreeSet::new();
+        let mut queue = VecDeque::from([path.as_ref().to_path_buf()]);
+        while let Some(path) = queue.pop_front() {
+            for edge in self.edges_to(&path) {
+                if dependents.insert(edge.from.as_path()) {
+                    queue.push_back(edge.from.clone());
+                }
+            }
+        }
+        dependents
+    }
+
+    /// The tags whose target is only known at render time, e.g. `{% render block_type %}`.
+    pub fn dynamic_edges(&self) -> impl Iterator<Item = &GraphEdge> {
+        self.edges.iter().filter(|edge| edge.is_dynamic())
+    }
+
+    /// The graph in Graphviz DOT. Unresolved targets are dashed.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph render_graph {\n  rankdir=LR;\n  node [shape=box];\n");
+        for node in &self.nodes {
+            dot.push_str(&format!(
+                "  {} [label={}];\n",
+                dot_string(&node.path.to_string_lossy()),
+                dot_string(&format!("{}/{}", directory(node.kind), node.name))
+            ));
+        }
+        for edge in &self.edges {
+            let from = dot_string(&edge.from.to_string_lossy());
+            let label = dot_string(&edge.label());
+            match &edge.to {
+                Some(to) => dot.push_str(&format!(
+                    "  {} -> {} [label={}];\n",
+                    from,
+                    dot_string(&to.to_string_lossy()),
+                    label
+                )),
+                None => {
+                    let target = dot_string(&edge.unresolved_label());
+                    dot.push_str(&format!("  {} [style=dashed];\n", target));
+                    dot.push_str(&format!(
+                        "  {} -> {} [label={}, style=dashed];\n",
+                        from, target, label
+                    ));
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// The graph as a Mermaid flowchart. Unresolved targets are dotted.
+    pub fn to_mermaid(&self) -> String {
+        let mut ids: BTreeMap<String, String> = BTreeMap::new();
+        let mut mermaid = String::from("flowchart LR\n");
+        let mut declare = |key: String, label: String, mermaid: &mut String| {
+            let next = format!("n{}", ids.len());
+            ids.entry(key)
+                .or_insert_with(|| {
+                    mermaid.push_str(&format!("  {}[\"{}\"]\n", next, mermaid_text(&label)));
+                    next
+                })
+                .clone()
+        };
+
+        for node in &self.nodes {
+            let label = format!("{}/{}", directory(node.kind), node.name);
+            declare(
+                node.path.to_string_lossy().into_owned(),
+                label,
+                &mut mermaid,
+            );
+        }
+        let mut lines = Vec::new();
+        for edge in &self.edges {
+            let from = declare(
+                edge.from.to_string_lossy().into_owned(),
+                String::new(),
+                &mut mermaid,
+            );
+            let label = mermaid_text(&edge.label());
+            match &edge.to {
+                Some(to) => {
+                    let to = declare(
+                        to.to_string_lossy().into_owned(),
+                        String::new(),
+                        &mut mermaid,
+                    );
+                    lines.push(format!("  {} -->|\"{}\"| {}", from, label, to));
+                }
+                None => {
+                    let unresolved = edge.unresolved_label();
+                    let to = declare(unresolved.clone(), unresolved, &mut mermaid);
+                    lines.push(format!("  {} -.->|\"{}\"| {}", from, label, to));
+                }
+            }
+        }
+        for line in lines {
+            mermaid.push_str(&line);
+            mermaid.push('\n');
+        }
+        mermaid
+    }
+}
+
+/// The edges of one file: its tags in source order, then the types it names without a tag.
+fn file_edges(file: &ThemeFile, index: &ThemeIndex) -> Vec<GraphEdge> {
+    let mut edges = Vec::new();
+    if !file.is_json() {
+        for (tag, call) in parse_calls(&file.source, &EdgeKind::TAGS) {
+            if let Some(kind) = EdgeKind::from_tag(tag) {
+                edges.push(GraphEdge::new(file, kind, call, index));
+            }
+        }
+        for call in parse_content_for_calls(&file.source) {
+            let Some(block_type) = call.block_type else {
+                continue;
+            };
+            let target = RenderTarget::Static(block_type);
+            let mut edge = GraphEdge::named(file, EdgeKind::ContentFor, target, index);
+            edge.position = Some(call.position);
+            edge.arguments = call.arguments;
+            edges.push(edge);
+        }
+        edges.sort_by_key(|edge| edge.position.map(|position| position.start));
+    }
+
+    let references = References::of(file);
+    let named = |kind, names: Vec<String>| {
+        names
+            .into_iter()
+            .map(move |name| GraphEdge::named(file, kind, RenderTarget::Static(name), index))
+    };
+    edges.extend(named(EdgeKind::Section, references.sections));
+    edges.extend(named(EdgeKind::Block, references.declared_blocks));
+    edges
+}
+
+/// The files a file names, and whether it may render snippets or blocks it does not name.
+#[derive(Default)]
+pub(crate) struct References {
+    /// From `{% render %}` and `{% include %}`.
+    pub snippets: Vec<String>,
+    /// From static `{% content_for 'block' %}` tags.
+    pub blocks: Vec<String>,
+    /// Block types named without a tag: in the `blocks` and `presets` of a `{% schema %}`, or
+    /// in the sections of a JSON template or section group.
+    pub declared_blocks: Vec<String>,
+    /// The section types of a JSON template or section group.
+    pub sections: Vec<String>,
+    pub any_snippet: bool,
+    pub any_block: bool,
+}
+
+impl References {
+    pub fn of(file: &ThemeFile) -> Self {
+        let mut references = References::default();
+        if file.is_json() {
+            if let Some(json) = parse_json(&file.source) {
+                for section in json["sections"]
+                    .as_object()
+                    .into_iter()
+                    .flat_map(|s| s.values())
+                {
+                    if let Some(section_type) = section["type"].as_str() {
+                        references.sections.push(section_type.to_string());
+                    }
+                    references.add_block_types(&section["blocks"]);
+                }
+            }
+            return references;
+        }
+
+        for (_, call) in parse_calls(&file.source, &["render", "include"]) {
+            match call.target {
+                RenderTarget::Static(name) => references.snippets.push(name),
+                RenderTarget::Dynamic(_) => references.any_snippet = true,
+            }
+        }
+        for call in parse_content_for_calls(&file.source) {
+            match call.block_type {
+                Some(block_type) => references.blocks.push(block_type),
+                None => references.any_block |= call.kind == "block",
+            }
+        }
+        if let Some(schema) = schema(&file.source) {
+            references.add_block_types(&schema["blocks"]);
+            for preset in schema["presets"].as_array().into_iter().flatten() {
+                references.add_block_types(&preset["blocks"]);
+            }
+        }
+        references
+    }
+
+    /// Adds the `type` of every block of a list or map of blocks, and of their nested blocks.
+    fn add_block_types(&mut self, blocks: &Value) {
+        let blocks: Vec<&Value> = match blocks {
+            Value::Array(blocks) => blocks.iter().collect(),
+            Value::Object(blocks) => blocks.values().collect(),
+            _ => return,
+        };
+        for block in blocks {
+            match block["type"].as_str() {
+                Some("@theme") => self.any_block = true,
+                // App blocks live in apps, not in the theme
+                Some(block_type) if !block_type.starts_with('@') => {
+                    self.declared_blocks.push(block_type.to_string())
+                }
+                _ => {}
+            }
+            self.add_block_types(&block["blocks"]);
+        }
+    }
+}
+
+/// The JSON of a template or section group, which Shopify may start with a `/* ... */` comment.
+fn parse_json(source: &str) -> Option<Value> {
+    let source = source.trim_start();
+    let source = match source.strip_prefix("/*") {
+        Some(comment) => &comment[comment.find("*/")? + 2..],
+        None => source,
+    };
+    serde_json::from_str(source).ok()
+}
+
+/// The JSON of the `{% schema %}` of a section or block.
+fn schema(source: &str) -> Option<Value> {
+    let mut tags = LiquidTags::new(source);
+    let open = tags.find(|tag| tag.name == "schema")?;
+    let close = tags.find(|tag| tag.name == "endschema")?;
+    serde_json::from_str(&source[open.end..close.start]).ok()
+}
+
+/// The directory of a kind of file, e.g. `snippets`.
+fn directory(kind: ThemeFileKind) -> &'static str {
+    match kind {
+        ThemeFileKind::Snippet => "snippets",
+        ThemeFileKind::Block => "blocks",
+        ThemeFileKind::Section | ThemeFileKind::SectionGroup => "sections",
+        ThemeFileKind::Layout => "layout",
+        ThemeFileKind::Template => "templates",
+    }
+}
+
+fn dot_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Mermaid has no escapes inside quoted labels, only entity codes.
+fn mermaid_text(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+/// Finds the strongly connected components of the resolved edges with Tarjan's algorithm, and
+/// the shortest cycle through the first node of each one that has a cycle.
+fn find_cycles(nodes: &[GraphNode], edges: &[GraphEdge]) -> Vec<Vec<PathBuf>> {
+    let ids: BTreeMap<&Path, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(id, node)| (node.path.as_path(), id))
+        .collect();
+    let mut successors = vec![BTreeSet::new(); nodes.len()];
+    for edge in edges {
+        let from = ids.get(edge.from.as_path());
+        let to = edge.to.as_deref().and_then(|to| ids.get(to));
+        if let (Some(&from), Some(&to)) = (from, to) {
+            successors[from].insert(to);
+        }
+    }
+
+    let mut tarjan = Tarjan {
+        successors: &successors,
+        index: vec![None; nodes.len()],
+        low: vec![0; nodes.len()],
+        on_stack: vec![false; nodes.len()],
+        stack: Vec::new(),
+        next: 0,
+        components: Vec::new(),
+    };
+    for node in 0..nodes.len() {
+        if tarjan.index[node].is_none() {
+            tarjan.visit(node);
+        }
+    }
+
+    let mut cycles: Vec<Vec<PathBuf>> = tarjan
+        .components
+        .iter()
+        .filter_map(|component| shortest_cycle(&successors, component))
+        .map(|cycle| cycle.into_iter().map(|id| nodes[id].path.clone()).collect())
+        .collect();
+    cycles.sort();
+    cycles
+}
+
+struct Tarjan<'a> {
+    successors: &'a [BTreeSet<usize>],
+    index: Vec<Option<usize>>,
+    low: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    next: usize,
+    components: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_> {
+    fn visit(&mut self, node: usize) {
+        self.index[node] = Some(self.next);
+        self.low[node] = self.next;
+        self.next += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        let successors = self.successors;
+        for &successor in &successors[node] {
+            match self.index[successor] {
+                None => {
+                    self.visit(successor);
+                    self.low[node] = self.low[node].min(self.low[successor]);
+                }
+                Some(index) if self.on_stack[successor] => {
+                    self.low[node] = self.low[node].min(index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(self.low[node]) == self.index[node] {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+/// The shortest path from the first node of `component` back to itself, if there is one.
+fn shortest_cycle(successors: &[BTreeSet<usize>], component: &[usize]) -> Option<Vec<usize>> {
+    let start = *component.iter().min()?;
+    let mut parents: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        for &successor in &successors[node] {
+            if successor == start {
+                let mut cycle = vec![start, node];
+                let mut current = node;
+                while let Some(&parent) = parents.get(&current) {
+                    cycle.push(parent);
+                    current = parent;
+                }
+                cycle.reverse();
+                return Some(cycle);
+            }
+            if component.contains(&successor) && !parents.contains_key(&successor) {
+                parents.insert(successor, node);
+                queue.push_back(successor);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn theme() -> ThemeIndex {
+        let mut index = ThemeIndex::new("theme");
+        index.update_file("layout/theme.liquid", "{% section 'header' %}");
+        index.update_file(
+            "sections/header.liquid",
+            "{% render 'menu', links: linklists.main.links %}{% render block.type %}",
+        );
+        index.update_file(
+            "snippets/menu.liquid",
+            "{% for link in links %}{% render 'menu-item' with link as item, depth: 1 %}{% endfor %}",
+        );
+        index.update_file(
+            "snippets/menu-item.liquid",
+            "{% include 'menu', links: item.links %}{% render 'missing' %}",
+        );
+        index
+    }
+
+    fn paths(paths: &[&str]) -> Vec<PathBuf> {
+        paths.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn links_renders_includes_and_sections() {
+        let graph = RenderGraph::build(&theme());
+
+        let edges: Vec<(EdgeKind, String, Option<String>, Vec<&str>)> = graph
+            .edges
+            .iter()
+            .map(|edge| {
+                (
+                    edge.kind,
+                    edge.from.to_string_lossy().into_owned(),
+                    edge.to.as_ref().map(|to| to.to_string_lossy().into_owned()),
+                    edge.passed_names(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                (
+                    EdgeKind::Section,
+                    "layout/theme.liquid".to_string(),
+                    Some("sections/header.liquid".to_string()),
+                    vec![]
+                ),
+                (
+                    EdgeKind::Render,
+                    "sections/header.liquid".to_string(),
+                    Some("snippets/menu.liquid".to_string()),
+                    vec!["links"]
+                ),
+                (
+                    EdgeKind::Render,
+                    "sections/header.liquid".to_string(),
+                    None,
+                    vec![]
+                ),
+                (
+                    EdgeKind::Include,
+                    "snippets/menu-item.liquid".to_string(),
+                    Some("snippets/menu.liquid".to_string()),
+                    vec!["links"]
+                ),
+                (
+                    EdgeKind::Render,
+                    "snippets/menu-item.liquid".to_string(),
+                    None,
+                    vec![]
+                ),
+                (
+                    EdgeKind::Render,
+                    "snippets/menu.liquid".to_string(),
+                    Some("snippets/menu-item.liquid".to_string()),
+                    vec!["item", "depth"]
+                ),
+            ]
+        );
+        assert_eq!(graph.dynamic_edges().count(), 1);
+    }
+
+    #[test]
+    fn finds_cycles_and_dependents() {
+        let graph = RenderGraph::build(&theme());
+
+        assert_eq!(
+            graph.cycles,
+            vec![paths(&[
+                "snippets/menu-item.liquid",
+                "snippets/menu.liquid",
+                "snippets/menu-item.liquid"
+            ])]
+        );
+        assert_eq!(
+            graph.dependents("snippets/menu.liquid"),
+            BTreeSet::from([
+                Path::new("layout/theme.liquid"),
+                Path::new("sections/header.liquid"),
+                Path::new("snippets/menu-item.liquid"),
+                Path::new("snippets/menu.liquid"),
+            ])
+        );
+    }
+
+    #[test]
+    fn links_json_files_section_groups_and_blocks() {
+        let mut index = ThemeIndex::new("theme");
+        index.update_file("layout/theme.liquid", "{% sections 'header-group' %}");
+        index.update_file(
+            "sections/header-group.json",
+            r#"{"type": "header", "sections": {"h": {"type": "header"}}, "order": ["h"]}"#,
+        );
+        index.update_file(
+            "templates/product.json",
+            r#"{"sections": {"main": {"type": "main", "blocks": {"b": {"type": "buy"}}}}, "order": ["main"]}"#,
+        );
+        index.update_file(
+            "sections/main.liquid",
+            r#"{% content_for 'block', type: 'price', id: 'p', size: 2 %}{% schema %}{"blocks": [{"type": "buy"}, {"type": "@app"}]}{% endschema %}"#,
+        );
+        index.update_file("sections/header.liquid", "");
+        index.update_file("blocks/buy.liquid", "{% render 'button' %}");
+        index.update_file("blocks/price.liquid", "");
+        index.update_file("snippets/button.liquid", "");
+        let graph = RenderGraph::build(&index);
+
+        let edges: Vec<(EdgeKind, &str, Option<&str>, bool)> = graph
+            .edges
+            .iter()
+            .map(|edge| {
+                (
+                    edge.kind,
+                    edge.from.to_str().unwrap(),
+                    edge.to.as_deref().and_then(Path::to_str),
+                    edge.position.is_some(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                (
+                    EdgeKind::Render,
+                    "blocks/buy.liquid",
+                    Some("snippets/button.liquid"),
+                    true
+                ),
+                (
+                    EdgeKind::Sections,
+                    "layout/theme.liquid",
+                    Some("sections/header-group.json"),
+                    true
+                ),
+                (
+                    EdgeKind::Section,
+                    "sections/header-group.json",
+                    Some("sections/header.liquid"),
+                    false
+                ),
+                (
+                    EdgeKind::ContentFor,
+                    "sections/main.liquid",
+                    Some("blocks/price.liquid"),
+                    true
+                ),
+                (
+                    EdgeKind::Block,
+                    "sections/main.liquid",
+                    Some("blocks/buy.liquid"),
+                    false
+                ),
+                (
+                    EdgeKind::Section,
+                    "templates/product.json",
+                    Some("sections/main.liquid"),
+                    false
+                ),
+                (
+                    EdgeKind::Block,
+                    "templates/product.json",
+                    Some("blocks/buy.liquid"),
+                    false
+                ),
+            ]
+        );
+        assert_eq!(
+            graph
+                .edges_to("blocks/price.liquid")
+                .next()
+                .unwrap()
+                .passed_names(),
+            vec!["size"]
+        );
+        assert_eq!(
+            graph.dependents("snippets/button.liquid"),
+            BTreeSet::from([
+                Path::new("blocks/buy.liquid"),
+                Path::new("sections/main.liquid"),
+                Path::new("templates/product.json"),
+            ])
+        );
+        assert_eq!(
+            graph.dependents("sections/header.liquid"),
+            BTreeSet::from([
+                Path::new("layout/theme.liquid"),
+                Path::new("sections/header-group.json"),
+            ])
+        );
+    }
+
+    #[test]
+    fn extensions_link_to_their_own_files() {
+        let mut index = ThemeIndex::new("theme");
+        index.update_file("snippets/rating.liquid", "");
+        index.update_file("extensions/reviews/snippets/rating.liquid", "");
+        index.update_file(
+            "extensions/reviews/blocks/stars.liquid",
+            "{% render 'rating', score: 3 %}{% content_for 'block', type: 'badge', id: 'a' %}",
+        );
+        index.update_file("blocks/badge.liquid", "");
+        let graph = RenderGraph::build(&index);
+
+        let targets: Vec<Option<&Path>> = graph
+            .edges_from("extensions/reviews/blocks/stars.liquid")
+            .map(|edge| edge.to.as_deref())
+            .collect();
+        assert_eq!(
+            targets,
+            vec![
+                Some(Path::new("extensions/reviews/snippets/rating.liquid")),
+                // The theme's blocks are not the extension's
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_self_renders() {
+        let mut index = ThemeIndex::new("theme");
+        index.update_file("snippets/tree.liquid", "{% render 'tree' %}");
+
+        assert_eq!(
+            RenderGraph::build(&index).cycles,
+            vec![paths(&["snippets/tree.liquid", "snippets/tree.liquid"])]
+        );
+    }
+
+    #[test]
+    fn exports_dot_and_mermaid() {
+        let mut index = ThemeIndex::new("theme");
+        index.update_file(
+            "sections/main.liquid",
+            "{% render 'card', title: 'Hi' %}{% render name %}",
+        );
+        index.update_file("snippets/card.liquid", "");
+        let graph = RenderGraph::build(&index);
+
+        assert_eq!(
+            graph.to_dot(),
+            r#"digraph render_graph {
+  rankdir=LR;
+  node [shape=box];
+  "sections/main.liquid" [label="sections/main"];
+  "snippets/card.liquid" [label="snippets/card"];
+  "sections/main.liquid" -> "snippets/card.liquid" [label="render: title"];
+  "dynamic name" [style=dashed];
+  "sections/main.liquid" -> "dynamic name" [label="render", style=dashed];
+}
+"#
+        );
+        assert_eq!(
+            graph.to_mermaid(),
+            r#"flowchart LR
+  n0["sections/main"]
+  n1["snippets/card"]
+  n2["dynamic name"]
+  n0 -->|"render: title"| n1
+  n0 -.->|"render"| n2
+"#
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use serde_json::{json, Value};
+
+use crate::ast::{LiquidAST, AST_VERSION};
+
+/// The checked-in schema, regenerated with `LIQUID_DOC_UPDATE_SCHEMA=1 cargo test`.
+pub const SCHEMA_PATH: &str = "schema/liquid-doc-ast.schema.json";
+
+/// JSON Schema (draft 7) of a serialised `LiquidAST`.
+///
+/// Nodes are closed: a field that is not in the schema fails validation, so every change to
+/// the serialised shape shows up as a schema change. `$id` carries `AST_VERSION`.
+pub fn liquid_ast_schema() -> Value {
+    let mut schema =
+        serde_json::to_value(schemars::schema_for!(LiquidAST)).expect("Schemas serialise to JSON");
+    // schemars only reads `#[serde(tag)]` on enums, so add the root's tag by hand
+    schema["properties"]["type"] = json!({ "type": "string", "enum": ["LiquidDoc"] });
+    if let Some(required) = schema["required"].as_array_mut() {
+        required.push(json!("type"));
+    }
+    close_objects(&mut schema);
+    schema["$id"] = json!(format!("urn:liquid-doc:ast:v{}", AST_VERSION));
+    schema
+}
+
+fn close_objects(schema: &mut Value) {
+    match schema {
+        Value::Object(object) => {
+            if object.contains_key("properties") {
+                object.insert("additionalProperties".to_string(), Value::Bool(false));
+            }
+            object.values_mut().for_each(close_objects);
+        }
+        Value::Array(items) => items.iter_mut().for_each(close_objects),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs, path::Path};
+
+    fn manifest_path(path: &str) -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join(path)
+    }
+
+    #[test]
+    fn checked_in_schema_is_up_to_date() {
+        let path = manifest_path(SCHEMA_PATH);
+        let generated = serde_json::to_string_pretty(&liquid_ast_schema()).unwrap() + "\n";
+        if env::var_os("LIQUID_DOC_UPDATE_SCHEMA").is_some() {
+            fs::write(&path, &generated).unwrap();
+        }
+
+        let checked_in = fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            checked_in == generated,
+            "{} is out of date, regenerate it with LIQUID_DOC_UPDATE_SCHEMA=1 cargo test",
+            SCHEMA_PATH
+        );
+    }
+
+    #[test]
+    fn snapshots_match_the_schema() {
+        let schema = liquid_ast_schema();
+        // Snapshots hold the root's `nodes`
+        let nodes_schema = json!({
+            "definitions": schema["definitions"],
+            "type": "array",
+            "items": { "$ref": "#/definitions/LiquidNode" },
+        });
+        let validator = jsonschema::JSONSchema::compile(&nodes_schema).unwrap();
+
+        let snapshots = fs::read_dir(manifest_path("src/ast/snapshots")).unwrap();
+        let mut validated = 0;
+        for entry in snapshots {
+            let path = entry.unwrap().path();
+            let snapshot = fs::read_to_string(&path).unwrap();
+            // The JSON follows insta's `---` delimited header
+            let json = snapshot.splitn(3, "---\n").nth(2).unwrap();
+            let nodes: Value = serde_json::from_str(json).unwrap();
+
+            if let Err(errors) = validator.validate(&nodes) {
+                let errors: Vec<String> = errors
+                    .map(|error| format!("{} at {}", error, error.instance_path))
+                    .collect();
+                panic!(
+                    "{} does not match the schema:\n{}",
+                    path.display(),
+                    errors.join("\n")
+                );
+            }
+            validated += 1;
+        }
+        assert!(validated > 0);
+    }
+
+    #[test]
+    fn root_matches_the_schema() {
+        let validator = jsonschema::JSONSchema::compile(&liquid_ast_schema()).unwrap();
+        let ast = crate::parse_liquid_string("@param {string} title", Some(9)).unwrap();
+
+        assert!(validator.is_valid(&serde_json::to_value(&ast).unwrap()));
+        assert!(!validator.is_valid(&json!({ "type": "LiquidDoc", "nodes": [] })));
+    }
+}
@@ -0,0 +1,188 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{self, Write};
+
+use serde::ser::{Error as _, SerializeSeq, SerializeStruct};
+use serde::{Serialize, Serializer};
+
+use crate::ast::{LiquidAST, LiquidNode};
+use crate::parser::{parse_liquid_nodes, ParseOptions};
+
+/// How [`write_liquid_json`] frames its output.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum StreamFormat {
+    /// The `LiquidDoc` root as `serde_json::to_string` writes it.
+    #[default]
+    Compact,
+    /// The `LiquidDoc` root as `serde_json::to_string_pretty` writes it.
+    Pretty,
+    /// One compact node per line, without the root.
+    Ndjson,
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+    /// The input is not LiquidDoc content. Nothing was written.
+    Unparseable,
+    Json(serde_json::Error),
+    Io(io::Error),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Unparseable => write!(f, "Could not parse the LiquidDoc content"),
+            StreamError::Json(error) => write!(f, "Could not write the JSON: {}", error),
+            StreamError::Io(error) => write!(f, "Could not write the output: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+impl From<serde_json::Error> for StreamError {
+    fn from(error: serde_json::Error) -> Self {
+        StreamError::Json(error)
+    }
+}
+
+impl From<io::Error> for StreamError {
+    fn from(error: io::Error) -> Self {
+        StreamError::Io(error)
+    }
+}
+
+/// Parses `input` and writes it to `writer` as JSON, one node at a time.
+///
+/// The parser still reads the whole input up front, but each node is serialised as soon as it
+/// is built and dropped before the next one, so the nodes of the `LiquidAST` are never all held
+/// at once. The output matches serialising the whole `LiquidAST`. Nothing is written when
+/// `input` does not parse.
+///
+/// ```
+/// use liquid_doc_parser::{write_liquid_json, ParseOptions, StreamFormat};
+///
+/// let mut output = Vec::new();
+/// let input = "@param {string} title\n@param {number} width";
+/// write_liquid_json(input, &ParseOptions::new(), &mut output, StreamFormat::Ndjson).unwrap();
+/// assert_eq!(String::from_utf8(output).unwrap().lines().count(), 2);
+/// ```
+pub fn write_liquid_json<W: Write>(
+    input: &str,
+    options: &ParseOptions,
+    mut writer: W,
+    format: StreamFormat,
+) -> Result<(), StreamError> {
+    let nodes = parse_liquid_nodes(input, options).ok_or(StreamError::Unparseable)?;
+
+    match format {
+        StreamFormat::Ndjson => {
+            for node in nodes {
+                serde_json::to_writer(&mut writer, &node)?;
+                writer.write_all(b"\n")?;
+            }
+        }
+        StreamFormat::Compact | StreamFormat::Pretty => {
+            let root = StreamedAst {
+                root: LiquidAST::for_source(input.len(), options.position_offset),
+                nodes: RefCell::new(Some(nodes)),
+            };
+            if format == StreamFormat::Pretty {
+                serde_json::to_writer_pretty(&mut writer, &root)?;
+            } else {
+                serde_json::to_writer(&mut writer, &root)?;
+            }
+        }
+    }
+
+    Ok(writer.flush()?)
+}
+
+/// Serialises like `LiquidAST`, pulling `nodes` from the parser while writing them.
+struct StreamedAst<I> {
+    /// The root fields, with no nodes.
+    root: LiquidAST,
+    nodes: RefCell<Option<I>>,
+}
+
+impl<I: Iterator<Item = LiquidNode>> Serialize for StreamedAst<I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut root = serializer.serialize_struct("LiquidAST", 6)?;
+        root.serialize_field("type", "LiquidDoc")?;
+        root.serialize_field("position", &self.root.position)?;
+        root.serialize_field("positionOffset", &self.root.position_offset)?;
+        root.serialize_field("parserVersion", &self.root.parser_version)?;
+        root.serialize_field("astVersion", &self.root.ast_version)?;
+        root.serialize_field("nodes", &StreamedNodes(&self.nodes))?;
+        root.end()
+    }
+}
+
+struct StreamedNodes<'a, I>(&'a RefCell<Option<I>>);
+
+impl<I: Iterator<Item = LiquidNode>> Serialize for StreamedNodes<'_, I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let nodes = self
+            .0
+            .borrow_mut()
+            .take()
+            .ok_or_else(|| S::Error::custom("The nodes were already written"))?;
+
+        let mut seq = serializer.serialize_seq(None)?;
+        for node in nodes {
+            seq.serialize_element(&node)?;
+        }
+        seq.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_liquid_string_with_options;
+    use pretty_assertions::assert_eq;
+
+    fn streamed(input: &str, options: &ParseOptions, format: StreamFormat) -> String {
+        let mut output = Vec::new();
+        write_liquid_json(input, options, &mut output, format).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn matches_serialising_the_whole_ast() {
+        let input = include_str!("../../web/fixtures/fixture1.liquid");
+        let options = ParseOptions::new().with_position_offset(Some(10));
+        let ast = parse_liquid_string_with_options(input, &options).unwrap();
+
+        assert_eq!(
+            streamed(input, &options, StreamFormat::Compact),
+            serde_json::to_string(&ast).unwrap()
+        );
+        assert_eq!(
+            streamed(input, &options, StreamFormat::Pretty),
+            serde_json::to_string_pretty(&ast).unwrap()
+        );
+    }
+
+    #[test]
+    fn ndjson_writes_a_node_per_line() {
+        let input = include_str!("../../web/fixtures/fixture1.liquid");
+        let options = ParseOptions::new();
+        let ast = parse_liquid_string_with_options(input, &options).unwrap();
+
+        let lines: Vec<LiquidNode> = streamed(input, &options, StreamFormat::Ndjson)
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines, ast.nodes);
+    }
+
+    #[test]
+    fn empty_input_has_no_nodes() {
+        assert_eq!(
+            streamed("", &ParseOptions::new(), StreamFormat::Compact),
+            serde_json::to_string(&LiquidAST::for_source(0, None)).unwrap()
+        );
+        assert_eq!(streamed("", &ParseOptions::new(), StreamFormat::Ndjson), "");
+    }
+}
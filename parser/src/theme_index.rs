@@ -0,0 +1,486 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::ast::LiquidAST;
+use crate::diagnostics::Diagnostic;
+use crate::liquid_doc::LiquidDoc;
+use crate::liquid_file::{parse_liquid_file, DocBlock};
+
+/// The kinds of indexed theme files. Snippets, blocks and sections can carry a `{% doc %}`
+/// block; layouts, templates and section groups are indexed for what they render.
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeFileKind {
+    Snippet,
+    Block,
+    Section,
+    Layout,
+    /// A `templates/` file, either Liquid or the JSON of its sections.
+    Template,
+    /// The JSON of a `sections/*.json` group, rendered with `{% sections %}`.
+    #[serde(rename = "sectionGroup")]
+    SectionGroup,
+}
+
+impl ThemeFileKind {
+    /// The kind of the files in a theme directory, e.g. `snippets`.
+    fn from_directory(directory: &str) -> Option<Self> {
+        match directory {
+            "snippets" => Some(ThemeFileKind::Snippet),
+            "blocks" => Some(ThemeFileKind::Block),
+            "sections" => Some(ThemeFileKind::Section),
+            "layout" => Some(ThemeFileKind::Layout),
+            "templates" => Some(ThemeFileKind::Template),
+            _ => None,
+        }
+    }
+
+    /// Theme app extensions only have snippets and blocks.
+    fn in_extensions(self) -> bool {
+        matches!(self, ThemeFileKind::Snippet | ThemeFileKind::Block)
+    }
+}
+
+/// An indexed theme file.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ThemeFile {
+    /// Relative to the theme root, e.g. `snippets/card.liquid`.
+    pub path: PathBuf,
+    pub kind: ThemeFileKind,
+    /// The file name without `.liquid`, as passed to `{% render %}` or used as a block `type`.
+    /// Customer templates keep their directory, e.g. `customers/account`.
+    pub name: String,
+    /// FNV-1a hash of the content, as 16 hex digits.
+    #[serde(rename = "contentHash")]
+    pub content_hash: String,
+    /// The first `{% doc %}` block, if the file has one.
+    #[serde(rename = "docBlock")]
+    pub doc_block: Option<DocBlock>,
+    /// What the doc block documents.
+    pub doc: Option<LiquidDoc>,
+    /// Problems finding the doc blocks, e.g. a missing `{% enddoc %}`.
+    pub diagnostics: Vec<Diagnostic>,
+    #[serde(skip)]
+    pub source: String,
+}
+
+impl ThemeFile {
+    fn new(path: PathBuf, kind: ThemeFileKind, name: String, source: String) -> Self {
+        let file = parse_liquid_file(&source);
+        let doc_block = file.doc_blocks.into_iter().next();
+        ThemeFile {
+            path,
+            kind,
+            name,
+            content_hash: content_hash(&source),
+            doc: doc_block
+                .as_ref()
+                .map(|block| LiquidDoc::from_ast(&block.ast)),
+            doc_block,
+            diagnostics: file.diagnostics,
+            source,
+        }
+    }
+
+    pub fn ast(&self) -> Option<&LiquidAST> {
+        self.doc_block.as_ref().map(|block| &block.ast)
+    }
+
+    pub fn is_documented(&self) -> bool {
+        self.doc_block.is_some()
+    }
+
+    /// The theme app extension the file belongs to, e.g. `reviews` for
+    /// `extensions/reviews/blocks/stars.liquid`, or `None` for the theme's own files.
+    pub fn extension(&self) -> Option<&str> {
+        match self.path.strip_prefix("extensions") {
+            Ok(path) => path.components().next()?.as_os_str().to_str(),
+            Err(_) => None,
+        }
+    }
+
+    /// Whether the file is a JSON template or section group rather than Liquid.
+    pub fn is_json(&self) -> bool {
+        self.path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+    }
+}
+
+/// A diagnostic in one of the files of a theme.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ThemeDiagnostic {
+    /// Relative to the theme root.
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub diagnostic: Diagnostic,
+}
+
+/// The Liquid and JSON files of a theme or theme app extension, kept in memory.
+///
+/// Loads `snippets/`, `blocks/`, `sections/`, `layout/` and `templates/` (including
+/// `templates/customers/`) at the root, with the JSON templates and section groups, and
+/// `snippets/` and `blocks/` of every theme app extension under `extensions/`. Editors keep it
+/// current with [`ThemeIndex::update_file`] and [`ThemeIndex::remove_file`], which only reparse
+/// changed content.
+///
+/// ```
+/// use liquid_doc_parser::ThemeIndex;
+///
+/// let mut index = ThemeIndex::new("theme");
+/// let source = "{% doc %}@param {string} title{% enddoc %}<h2>{{ title }}</h2>";
+/// index.update_file("theme/snippets/heading.liquid", source);
+///
+/// index.update_file("theme/sections/main.liquid", "{% render 'heading', title: 'Hi' %}");
+///
+/// let main = index.section("main").unwrap();
+/// let heading = index.snippet("heading", main).unwrap();
+/// assert!(heading.doc.as_ref().unwrap().param("title").is_some());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThemeIndex {
+    root: PathBuf,
+    files: BTreeMap<PathBuf, ThemeFile>,
+}
+
+impl ThemeIndex {
+    /// An empty index of the theme at `root`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        ThemeIndex {
+            root: root.into(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Reads and indexes every theme file under `root`.
+    /// Missing directories are skipped.
+    pub fn load(root: impl Into<PathBuf>) -> io::Result<Self> {
+        let mut index = ThemeIndex::new(root);
+
+        let mut directories = vec![PathBuf::new()];
+        match fs::read_dir(index.root.join("extensions")) {
+            Ok(extensions) => {
+                for extension in extensions {
+                    let extension = extension?;
+                    if extension.file_type()?.is_dir() {
+                        directories.push(Path::new("extensions").join(extension.file_name()));
+                    }
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+
+        for directory in directories {
+            for kind_directory in DIRECTORIES {
+                let entries = match fs::read_dir(index.root.join(&directory).join(kind_directory)) {
+                    Ok(entries) => entries,
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                    Err(e) => return Err(e),
+                };
+                for entry in entries {
+                    let path = directory.join(kind_directory).join(entry?.file_name());
+                    if classify(&path).is_some() {
+                        let source = fs::read_to_string(index.root.join(&path))?;
+                        index.update_file(&path, &source);
+                    }
+                }
+            }
+        }
+
+        Ok(index)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Indexes `path` with `source`, e.g. an unsaved editor buffer. `path` may be absolute or
+    /// relative to the root. Returns `false` when the file is not an indexed theme file, or when
+    /// its content did not change.
+    pub fn update_file(&mut self, path: impl AsRef<Path>, source: &str) -> bool {
+        let Some(path) = self.relative_path(path.as_ref()) else {
+            return false;
+        };
+        let Some((kind, name)) = classify(&path) else {
+            return false;
+        };
+        if let Some(file) = self.files.get(&path) {
+            if file.content_hash == content_hash(source) {
+                return false;
+            }
+        }
+
+        let file = ThemeFile::new(path.clone(), kind, name, source.to_string());
+        self.files.insert(path, file);
+        true
+    }
+
+    /// Drops `path` from the index, returning what was indexed for it.
+    pub fn remove_file(&mut self, path: impl AsRef<Path>) -> Option<ThemeFile> {
+        let path = self.relative_path(path.as_ref())?;
+        self.files.remove(&path)
+    }
+
+    /// Every indexed file, ordered by path.
+    pub fn files(&self) -> impl Iterator<Item = &ThemeFile> {
+        self.files.values()
+    }
+
+    pub fn file(&self, path: impl AsRef<Path>) -> Option<&ThemeFile> {
+        self.files.get(&self.relative_path(path.as_ref())?)
+    }
+
+    /// Finds a file by kind and name as `from` would name it: the files of a theme app
+    /// extension only see their own extension, and the theme's files only the theme.
+    pub fn get(&self, kind: ThemeFileKind, name: &str, from: &ThemeFile) -> Option<&ThemeFile> {
+        self.find(kind, name, from.extension())
+    }
+
+    pub fn snippet(&self, name: &str, from: &ThemeFile) -> Option<&ThemeFile> {
+        self.get(ThemeFileKind::Snippet, name, from)
+    }
+
+    pub fn block(&self, name: &str, from: &ThemeFile) -> Option<&ThemeFile> {
+        self.get(ThemeFileKind::Block, name, from)
+    }
+
+    /// Finds one of the theme's sections, which extensions do not have.
+    pub fn section(&self, name: &str) -> Option<&ThemeFile> {
+        self.find(ThemeFileKind::Section, name, None)
+    }
+
+    fn find(&self, kind: ThemeFileKind, name: &str, extension: Option<&str>) -> Option<&ThemeFile> {
+        self.files()
+            .find(|file| file.kind == kind && file.name == name && file.extension() == extension)
+    }
+
+    fn relative_path(&self, path: &Path) -> Option<PathBuf> {
+        let path = if path.is_absolute() || path.starts_with(&self.root) {
+            path.strip_prefix(&self.root).ok()?
+        } else {
+            path
+        };
+        Some(path.to_path_buf())
+    }
+}
+
+/// The directories [`ThemeIndex::load`] reads, relative to the root or an extension.
+const DIRECTORIES: [&str; 6] = [
+    "snippets",
+    "blocks",
+    "sections",
+    "layout",
+    "templates",
+    "templates/customers",
+];
+
+/// The kind and name of a theme file from its path relative to the root, e.g.
+/// `snippets/card.liquid`, `templates/product.json` or `extensions/reviews/blocks/stars.liquid`.
+fn classify(path: &Path) -> Option<(ThemeFileKind, String)> {
+    let is_json = match path.extension()?.to_str()? {
+        "liquid" => false,
+        "json" => true,
+        _ => return None,
+    };
+    let components: Vec<&str> = path
+        .components()
+        .map(|component| match component {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+
+    let kind = match components[..] {
+        [directory, _] => ThemeFileKind::from_directory(directory)?,
+        ["templates", "customers", _] => {
+            let name = path.file_stem()?.to_str()?;
+            return Some((ThemeFileKind::Template, format!("customers/{}", name)));
+        }
+        ["extensions", _, directory, _] if !is_json => {
+            ThemeFileKind::from_directory(directory).filter(|kind| kind.in_extensions())?
+        }
+        _ => return None,
+    };
+    let kind = match (kind, is_json) {
+        (kind, false) => kind,
+        (ThemeFileKind::Template, true) => ThemeFileKind::Template,
+        (ThemeFileKind::Section, true) => ThemeFileKind::SectionGroup,
+        (_, true) => return None,
+    };
+    let name = path.file_stem()?.to_str()?.to_string();
+    Some((kind, name))
+}
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` is the same across Rust releases.
+fn content_hash(source: &str) -> String {
+    let hash = source.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const CARD: &str = "{% doc %}\n  Renders a card\n  @param {string} title\n{% enddoc %}\n<div>{{ title }}</div>";
+
+    fn temp_theme(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("liquid-doc-theme-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&root);
+        root
+    }
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn loads_a_theme_and_its_extensions() {
+        let root = temp_theme("load");
+        write(&root, "snippets/card.liquid", CARD);
+        write(&root, "blocks/slide.liquid", "<div></div>");
+        write(&root, "sections/header.liquid", CARD);
+        write(&root, "templates/customers/account.liquid", CARD);
+        write(&root, "templates/product.json", "{}");
+        write(&root, "sections/header-group.json", "{}");
+        write(&root, "snippets/settings.json", "{}");
+        write(&root, "assets/theme.liquid", CARD);
+        write(&root, "snippets/notes.txt", CARD);
+        write(&root, "extensions/reviews/blocks/stars.liquid", CARD);
+        write(&root, "extensions/reviews/sections/ignored.liquid", CARD);
+
+        let index = ThemeIndex::load(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        let paths: Vec<&Path> = index.files().map(|file| file.path.as_path()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                Path::new("blocks/slide.liquid"),
+                Path::new("extensions/reviews/blocks/stars.liquid"),
+                Path::new("sections/header-group.json"),
+                Path::new("sections/header.liquid"),
+                Path::new("snippets/card.liquid"),
+                Path::new("templates/customers/account.liquid"),
+                Path::new("templates/product.json"),
+            ]
+        );
+        let header = index.section("header").unwrap();
+        assert!(index
+            .get(ThemeFileKind::SectionGroup, "header-group", header)
+            .is_some());
+        assert!(index
+            .get(ThemeFileKind::Template, "product", header)
+            .unwrap()
+            .is_json());
+
+        let card = index.snippet("card", header).unwrap();
+        assert_eq!(card.kind, ThemeFileKind::Snippet);
+        assert_eq!(card.ast().unwrap().position_offset, 9);
+        assert_eq!(
+            card.doc.as_ref().unwrap().description_text(),
+            Some("Renders a card")
+        );
+        assert!(!index.block("slide", header).unwrap().is_documented());
+        assert_eq!(index.block("stars", header), None);
+        let stars = index
+            .file("extensions/reviews/blocks/stars.liquid")
+            .unwrap();
+        assert_eq!(stars.extension(), Some("reviews"));
+        assert_eq!(index.block("stars", stars), Some(stars));
+        assert!(index
+            .get(ThemeFileKind::Template, "customers/account", header)
+            .is_some());
+    }
+
+    #[test]
+    fn updates_only_changed_files() {
+        let mut index = ThemeIndex::new("/theme");
+
+        assert!(index.update_file("/theme/snippets/card.liquid", CARD));
+        let hash = index
+            .file("snippets/card.liquid")
+            .unwrap()
+            .content_hash
+            .clone();
+        assert!(!index.update_file("snippets/card.liquid", CARD));
+        assert!(!index.update_file("/theme/assets/theme.liquid", CARD));
+        assert!(!index.update_file("/elsewhere/snippets/card.liquid", CARD));
+
+        assert!(index.update_file("snippets/card.liquid", "<div></div>"));
+        let card = index.file("snippets/card.liquid").unwrap();
+        assert_ne!(card.content_hash, hash);
+        assert_eq!(card.doc, None);
+    }
+
+    #[test]
+    fn removes_files() {
+        let mut index = ThemeIndex::new("theme");
+        index.update_file("snippets/card.liquid", CARD);
+
+        assert_eq!(
+            index
+                .remove_file("theme/snippets/card.liquid")
+                .unwrap()
+                .name,
+            "card"
+        );
+        assert_eq!(index.file("snippets/card.liquid"), None);
+        assert_eq!(index.remove_file("snippets/card.liquid"), None);
+    }
+
+    #[test]
+    fn names_resolve_within_the_calling_files_scope() {
+        let mut index = ThemeIndex::new("theme");
+        index.update_file(
+            "snippets/rating.liquid",
+            "{% doc %}@param {string} label{% enddoc %}",
+        );
+        index.update_file(
+            "extensions/reviews/snippets/rating.liquid",
+            "{% doc %}@param {number} score{% enddoc %}",
+        );
+        index.update_file(
+            "extensions/reviews/blocks/stars.liquid",
+            "{% render 'rating', score: 3 %}",
+        );
+        index.update_file(
+            "extensions/other/blocks/badge.liquid",
+            "{% render 'rating' %}",
+        );
+        index.update_file("sections/main.liquid", "{% render 'rating', label: 'Hi' %}");
+
+        let path = |from: &str| {
+            let from = index.file(from).unwrap();
+            index
+                .snippet("rating", from)
+                .map(|file| file.path.as_path())
+        };
+        assert_eq!(
+            path("extensions/reviews/blocks/stars.liquid"),
+            Some(Path::new("extensions/reviews/snippets/rating.liquid"))
+        );
+        assert_eq!(
+            path("sections/main.liquid"),
+            Some(Path::new("snippets/rating.liquid"))
+        );
+        // Extensions cannot render the theme's snippets, nor another extension's
+        assert_eq!(path("extensions/other/blocks/badge.liquid"), None);
+    }
+
+    #[test]
+    fn content_hashes_are_stable() {
+        assert_eq!(content_hash(""), "cbf29ce484222325");
+        assert_eq!(content_hash("a"), "af63dc4c8601ec8c");
+    }
+}
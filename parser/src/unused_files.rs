@@ -0,0 +1,287 @@
+use std::collections::{BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::render_graph::References;
+use crate::theme_index::{ThemeFile, ThemeFileKind, ThemeIndex};
+
+/// How sure the analysis is that a file is never rendered.
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum UnusedConfidence {
+    /// Nothing can render the file.
+    Certain,
+    /// No tag names the file, but a dynamic one might, such as `{% render block_type %}` for a
+    /// snippet, or a section accepting any `@theme` block for a block, in the same theme or
+    /// extension.
+    Possible,
+}
+
+/// A snippet or block that no rendered file references.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct UnusedFile {
+    pub path: PathBuf,
+    pub kind: ThemeFileKind,
+    pub name: String,
+    pub confidence: UnusedConfidence,
+    /// The description of the file's doc, without its indentation.
+    pub description: Option<String>,
+}
+
+/// Lists the snippets and blocks of a theme that are never rendered, ordered by path.
+///
+/// Layouts, templates, section groups, sections and the app blocks of theme app extensions are
+/// always rendered. From them, a file is rendered when a rendered file names it: snippets with
+/// `{% render %}` or `{% include %}`, blocks with a static `{% content_for 'block' %}`, the
+/// `blocks` and `presets` of a `{% schema %}`, or the block types of a JSON template or section
+/// group. A snippet only rendered by unused snippets is unused too.
+///
+/// ```
+/// use liquid_doc_parser::{find_unused_files, ThemeIndex, UnusedConfidence};
+///
+/// let mut index = ThemeIndex::new("theme");
+/// index.update_file("sections/main.liquid", "{% render 'card' %}");
+/// index.update_file("snippets/card.liquid", "");
+/// index.update_file("snippets/old-card.liquid", "{% doc %}Renders a card{% enddoc %}");
+///
+/// let unused = find_unused_files(&index);
+/// assert_eq!(unused[0].name, "old-card");
+/// assert_eq!(unused[0].confidence, UnusedConfidence::Certain);
+/// assert_eq!(unused[0].description.as_deref(), Some("Renders a card"));
+/// ```
+pub fn find_unused_files(index: &ThemeIndex) -> Vec<UnusedFile> {
+    let mut rendered: BTreeSet<&Path> = BTreeSet::new();
+    let mut queue: VecDeque<&ThemeFile> = VecDeque::new();
+    for file in index.files().filter(|file| is_entry_point(file)) {
+        rendered.insert(&file.path);
+        queue.push_back(file);
+    }
+
+    // The extensions, or `None` for the theme, whose files name a snippet or block dynamically
+    let mut any_snippet: BTreeSet<Option<&str>> = BTreeSet::new();
+    let mut any_block: BTreeSet<Option<&str>> = BTreeSet::new();
+    while let Some(file) = queue.pop_front() {
+        let references = References::of(file);
+        if references.any_snippet {
+            any_snippet.insert(file.extension());
+        }
+        if references.any_block {
+            any_block.insert(file.extension());
+        }
+
+        let snippets = references
+            .snippets
+            .iter()
+            .filter_map(|name| index.snippet(name, file));
+        let blocks = references
+            .blocks
+            .iter()
+            .chain(&references.declared_blocks)
+            .filter_map(|name| index.block(name, file));
+        for referenced in snippets.chain(blocks) {
+            if rendered.insert(&referenced.path) {
+                queue.push_back(referenced);
+            }
+        }
+    }
+
+    index
+        .files()
+        .filter(|file| !rendered.contains(file.path.as_path()))
+        .filter_map(|file| {
+            let possible = match file.kind {
+                ThemeFileKind::Snippet => any_snippet.contains(&file.extension()),
+                ThemeFileKind::Block => any_block.contains(&file.extension()),
+                _ => return None,
+            };
+            Some(UnusedFile {
+                path: file.path.clone(),
+                kind: file.kind,
+                name: file.name.clone(),
+                confidence: if possible {
+                    UnusedConfidence::Possible
+                } else {
+                    UnusedConfidence::Certain
+                },
+                description: file
+                    .doc
+                    .as_ref()
+                    .and_then(|doc| doc.description_text())
+                    .map(unindent)
+                    .filter(|description| !description.is_empty()),
+            })
+        })
+        .collect()
+}
+
+/// Files Shopify renders without a tag naming them. The blocks of a theme app extension are
+/// app blocks, which merchants add in the theme editor.
+fn is_entry_point(file: &ThemeFile) -> bool {
+    match file.kind {
+        ThemeFileKind::Snippet => false,
+        ThemeFileKind::Block => file.path.starts_with("extensions"),
+        _ => true,
+    }
+}
+
+fn unindent(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn unused(files: &[(&str, &str)]) -> Vec<(String, UnusedConfidence)> {
+        let mut index = ThemeIndex::new("theme");
+        for (path, source) in files {
+            index.update_file(path, source);
+        }
+        find_unused_files(&index)
+            .into_iter()
+            .map(|file| (file.path.to_string_lossy().into_owned(), file.confidence))
+            .collect()
+    }
+
+    #[test]
+    fn follows_every_kind_of_reference() {
+        let files = [
+            ("layout/theme.liquid", "{% render 'meta' %}{% sections 'header-group' %}"),
+            (
+                "templates/product.json",
+                "/* Generated */\n{\"sections\": {\"main\": {\"type\": \"main\", \"blocks\": {\"a\": {\"type\": \"buy\", \"blocks\": {\"b\": {\"type\": \"price\"}}}}}}}",
+            ),
+            (
+                "sections/main.liquid",
+                "{% content_for 'block', type: 'badge', id: 'b' %}\n{% schema %}{\"blocks\": [{\"type\": \"@app\"}, {\"type\": \"slide\"}], \"presets\": [{\"name\": \"Main\", \"blocks\": [{\"type\": \"gallery\"}]}]}{% endschema %}",
+            ),
+            ("snippets/meta.liquid", "{% include 'meta-tags' %}"),
+            ("snippets/meta-tags.liquid", ""),
+            ("blocks/buy.liquid", ""),
+            ("blocks/price.liquid", "{% render 'money' %}"),
+            ("blocks/badge.liquid", ""),
+            ("blocks/slide.liquid", ""),
+            ("blocks/gallery.liquid", ""),
+            ("snippets/money.liquid", ""),
+            ("snippets/dead.liquid", "{% render 'dead-child' %}"),
+            ("snippets/dead-child.liquid", ""),
+            ("blocks/dead.liquid", "{% render block.type %}"),
+        ];
+
+        assert_eq!(
+            unused(&files),
+            vec![
+                ("blocks/dead.liquid".to_string(), UnusedConfidence::Certain),
+                (
+                    "snippets/dead-child.liquid".to_string(),
+                    UnusedConfidence::Certain
+                ),
+                (
+                    "snippets/dead.liquid".to_string(),
+                    UnusedConfidence::Certain
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn dynamic_references_make_files_possibly_used() {
+        let files = [
+            ("sections/main.liquid", "{% render name %}{% schema %}{\"blocks\": [{\"type\": \"@theme\"}]}{% endschema %}"),
+            ("snippets/card.liquid", ""),
+            ("blocks/slide.liquid", ""),
+        ];
+
+        assert_eq!(
+            unused(&files),
+            vec![
+                (
+                    "blocks/slide.liquid".to_string(),
+                    UnusedConfidence::Possible
+                ),
+                (
+                    "snippets/card.liquid".to_string(),
+                    UnusedConfidence::Possible
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn app_blocks_and_their_snippets_are_used() {
+        let files = [
+            (
+                "extensions/reviews/blocks/stars.liquid",
+                "{% render 'rating' %}",
+            ),
+            ("extensions/reviews/snippets/rating.liquid", ""),
+            ("extensions/reviews/snippets/unused.liquid", ""),
+        ];
+
+        assert_eq!(
+            unused(&files),
+            vec![(
+                "extensions/reviews/snippets/unused.liquid".to_string(),
+                UnusedConfidence::Certain
+            )]
+        );
+    }
+
+    #[test]
+    fn names_resolve_within_each_extension() {
+        let files = [
+            (
+                "extensions/reviews/blocks/stars.liquid",
+                "{% render 'rating' %}{% render name %}",
+            ),
+            ("extensions/reviews/snippets/rating.liquid", ""),
+            ("extensions/reviews/snippets/summary.liquid", ""),
+            ("sections/main.liquid", "{% render 'badge' %}"),
+            ("snippets/rating.liquid", ""),
+            ("extensions/other/snippets/badge.liquid", ""),
+        ];
+
+        assert_eq!(
+            unused(&files),
+            vec![
+                (
+                    "extensions/other/snippets/badge.liquid".to_string(),
+                    UnusedConfidence::Certain
+                ),
+                // Only snippets of the same extension are reachable from its dynamic render
+                (
+                    "extensions/reviews/snippets/summary.liquid".to_string(),
+                    UnusedConfidence::Possible
+                ),
+                (
+                    "snippets/rating.liquid".to_string(),
+                    UnusedConfidence::Certain
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_the_doc_description() {
+        let mut index = ThemeIndex::new("theme");
+        index.update_file(
+            "snippets/card.liquid",
+            "{% doc %}\n  Renders a card\n  with an image\n\n  @param {string} title\n{% enddoc %}",
+        );
+        index.update_file("snippets/empty.liquid", "");
+
+        let unused = find_unused_files(&index);
+        assert_eq!(
+            unused[0].description.as_deref(),
+            Some("Renders a card\nwith an image")
+        );
+        assert_eq!(unused[1].description, None);
+    }
+}
@@ -1,3 +1,4 @@
+use liquid_doc_parser::ParseOptions;
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
@@ -11,9 +12,12 @@ macro_rules! console_log {
     ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
 }
 
+/// Unknown tags become `LiquidDocUnknownTagNode`s unless `fallback_text_nodes` is set, which
+/// keeps them `TextNode`s for parity with liquid-html-parser.
 #[wasm_bindgen]
-pub fn parse_liquid(input: &str) -> JsValue {
-    match liquid_doc_parser::parse_liquid_string(input, None) {
+pub fn parse_liquid(input: &str, fallback_text_nodes: Option<bool>) -> JsValue {
+    let options = options(fallback_text_nodes);
+    match liquid_doc_parser::parse_liquid_string_with_options(input, &options) {
         Some(ast) => {
             serde_wasm_bindgen::to_value(&ast).expect("The LiquidAst was not in the correct format")
         }
@@ -21,5 +25,18 @@ pub fn parse_liquid(input: &str) -> JsValue {
     }
 }
 
+/// Parses every `{% doc %}` block of a whole template, e.g. a snippet file. Unknown tags are
+/// handled as in [`parse_liquid`].
+#[wasm_bindgen]
+pub fn parse_liquid_file(input: &str, fallback_text_nodes: Option<bool>) -> JsValue {
+    let options = options(fallback_text_nodes);
+    let file = liquid_doc_parser::parse_liquid_file_with_options(input, &options);
+    serde_wasm_bindgen::to_value(&file).expect("The LiquidFile was not in the correct format")
+}
+
+fn options(fallback_text_nodes: Option<bool>) -> ParseOptions {
+    ParseOptions::new().with_fallback_text_nodes(fallback_text_nodes.unwrap_or(false))
+}
+
 // Re-export the parser function for other Rust crates
 pub use liquid_doc_parser::parse_liquid_string;
@@ -99,7 +99,7 @@ function testFixture(fixture, iterations = 100) {
   ];
 
   if (pestParser && pestParser.parse_liquid) {
-    benchmarks.push(["Pest.rs", () => pestParser.parse_liquid(pestInput)]);
+    benchmarks.push(["Pest.rs", () => pestParser.parse_liquid(pestInput, true)]);
   }
 
   return benchmarkComparison(benchmarks, iterations);